@@ -0,0 +1,135 @@
+// Copyright (C) 2019 University of Glasgow
+// 
+// Redistribution and use in source and binary forms, with or without
+// modification, are permitted provided that the following conditions 
+// are met:
+// 
+// 1. Redistributions of source code must retain the above copyright notice,
+//    this list of conditions and the following disclaimer.
+// 
+// 2. Redistributions in binary form must reproduce the above copyright
+//    notice, this list of conditions and the following disclaimer in the
+//    documentation and/or other materials provided with the distribution.
+// 
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+// AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+// IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE
+// ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE
+// LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR
+// CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF
+// SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS
+// INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN
+// CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE)
+// ARISING IN ANY WAY OUT OF THE USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE
+// POSSIBILITY OF SUCH DAMAGE.
+//
+// SPDX-License-Identifier: BSD-2-Clause
+
+
+// IESG ballots on documents. A ballot is opened by a BallotDocEvent of type
+// "created_ballot", and each Area Director records their position using a
+// BallotPositionDocEvent that refers to it. ADs can change their position,
+// in which case only the latest counts.
+
+use std::collections::{HashMap, HashSet};
+
+use serde::Deserialize;
+
+use super::docevent::{BallotDocEvent, BallotPositionDocEvent};
+use super::name::{BallotPosition, DocType};
+use super::person::PersonUri;
+
+// --------------------------------------------------------------------------------------------------------------------------------
+// Types relating to ballots:
+
+#[derive(Deserialize, Debug, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct BallotTypeUri(pub String);
+
+
+// A type of ballot that can be issued on a document, e.g., "approve"
+#[derive(Deserialize, Debug)]
+pub struct BallotType {
+    pub id           : u64,
+    pub resource_uri : BallotTypeUri,
+    pub doc_type     : Option<DocType>,
+    pub slug         : String,
+    pub name         : String,
+    pub question     : String,
+    pub used         : bool,
+    pub order        : u64,
+    pub positions    : Vec<BallotPosition>
+}
+
+
+// An entry in the table of ballot positions
+#[derive(Deserialize, Debug)]
+pub struct BallotPositionName {
+    pub resource_uri : BallotPosition,
+    pub slug         : String,
+    pub name         : String,
+    pub desc         : String,
+    pub used         : bool,
+    pub order        : u64,
+    pub blocking     : bool
+}
+
+
+// The most recent ballot on a document, with the latest position of each
+// balloter, ordered by the time that position was recorded
+#[derive(Debug)]
+pub struct Ballot {
+    pub ballot    : BallotDocEvent,
+    pub closed    : bool,
+    pub positions : Vec<BallotPositionDocEvent>
+}
+
+
+impl Ballot {
+    // The positions must all be for the given ballot
+    pub(crate) fn new(ballot : BallotDocEvent, closed : bool, positions : Vec<BallotPositionDocEvent>) -> Self {
+        let mut latest : HashMap<PersonUri, BallotPositionDocEvent> = HashMap::new();
+        for pos in positions {
+            match latest.get(&pos.balloter) {
+                Some(prev) if (prev.time, prev.id) > (pos.time, pos.id) => {}
+                _ => {
+                    latest.insert(pos.balloter.clone(), pos);
+                }
+            }
+        }
+        let mut positions = latest.into_values().collect::<Vec<_>>();
+        positions.sort_by_key(|pos| (pos.time, pos.id));
+        Ballot {ballot, closed, positions}
+    }
+
+    // The latest position of the given balloter
+    pub fn position(&self, balloter : &PersonUri) -> Option<&BallotPositionDocEvent> {
+        self.positions.iter().find(|pos| &pos.balloter == balloter)
+    }
+
+    // The DISCUSS and BLOCK positions
+    pub fn blocking_positions(&self) -> Vec<&BallotPositionDocEvent> {
+        self.positions.iter().filter(|pos| pos.pos == BallotPosition::Discuss || pos.pos == BallotPosition::Block).collect()
+    }
+}
+
+
+// The most recently created ballot, and whether it has since been closed,
+// given the ballot events for a document, newest first
+pub(crate) fn latest_ballot(events : Vec<BallotDocEvent>) -> Option<(BallotDocEvent, bool)> {
+    let mut closed = HashSet::new();
+    for event in events {
+        match event.event_type.as_str() {
+            "closed_ballot"  => {
+                closed.insert(event.ballot_type.clone());
+            }
+            "created_ballot" => {
+                let is_closed = closed.contains(&event.ballot_type);
+                return Some((event, is_closed));
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+// --------------------------------------------------------------------------------------------------------------------------------
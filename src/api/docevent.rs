@@ -0,0 +1,509 @@
+// Copyright (C) 2019 University of Glasgow
+// 
+// Redistribution and use in source and binary forms, with or without
+// modification, are permitted provided that the following conditions 
+// are met:
+// 
+// 1. Redistributions of source code must retain the above copyright notice,
+//    this list of conditions and the following disclaimer.
+// 
+// 2. Redistributions in binary form must reproduce the above copyright
+//    notice, this list of conditions and the following disclaimer in the
+//    documentation and/or other materials provided with the distribution.
+// 
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+// AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+// IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE
+// ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE
+// LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR
+// CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF
+// SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS
+// INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN
+// CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE)
+// ARISING IN ANY WAY OUT OF THE USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE
+// POSSIBILITY OF SUCH DAMAGE.
+//
+// SPDX-License-Identifier: BSD-2-Clause
+
+
+// Events in the history of a document. Every event is a DocEvent, and most
+// are also one of the more specific types of event, that are listed at their
+// own endpoints and have additional fields. DocEvent::Base is used for
+// events that aren't any of the more specific types.
+
+use std::collections::{HashMap, HashSet};
+
+use chrono::prelude::*;
+use serde::Deserialize;
+
+use super::{deserialize_time, deserialize_optional_time, deserialize_optional_date};
+use super::ballot::BallotTypeUri;
+use super::person::PersonUri;
+use super::document::*;
+use super::name::BallotPosition;
+use super::submission::SubmissionUri;
+use crate::resource::Endpoint;
+
+// --------------------------------------------------------------------------------------------------------------------------------
+// Types referenced by document events, that are not yet otherwise supported:
+
+#[derive(Deserialize, Debug, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct ReviewRequestUri(pub String);
+
+
+#[derive(Deserialize, Debug, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct ReviewRequestStateUri(pub String);
+
+
+#[derive(Deserialize, Debug, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct MessageUri(pub String);
+
+
+// The type of a deleted object, e.g., "/api/v1/contenttypes/contenttype/29/"
+#[derive(Deserialize, Debug, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct ContentTypeUri(pub String);
+
+// --------------------------------------------------------------------------------------------------------------------------------
+// Types relating to document events:
+
+#[derive(Deserialize, Debug, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct DocEventUri(pub String);
+
+
+// The fields common to all document events
+#[derive(Deserialize, Debug)]
+pub struct BaseDocEvent {
+    pub id           : u64,
+    pub resource_uri : DocEventUri,
+    pub doc          : DocumentUri,
+    pub by           : PersonUri,
+    #[serde(deserialize_with="deserialize_time")]
+    pub time         : DateTime<Utc>,
+    #[serde(rename = "type")]
+    pub event_type   : String,
+    pub rev          : Option<String>,
+    pub desc         : String
+}
+
+
+#[derive(Deserialize, Debug, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct StateDocEventUri(pub String);
+
+
+#[derive(Deserialize, Debug)]
+pub struct StateDocEvent {
+    // Fields common with BaseDocEvent:
+    pub id           : u64,
+    pub resource_uri : StateDocEventUri,
+    pub doc          : DocumentUri,
+    pub by           : PersonUri,
+    #[serde(deserialize_with="deserialize_time")]
+    pub time         : DateTime<Utc>,
+    #[serde(rename = "type")]
+    pub event_type   : String,
+    pub rev          : Option<String>,
+    pub desc         : String,
+    // Fields specific to this type of event:
+    pub docevent_ptr : DocEventUri,
+    pub state        : Option<DocStateUri>,
+    pub state_type   : DocStateTypeUri
+}
+
+
+#[derive(Deserialize, Debug, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct BallotDocEventUri(pub String);
+
+
+#[derive(Deserialize, Debug)]
+pub struct BallotDocEvent {
+    // Fields common with BaseDocEvent:
+    pub id           : u64,
+    pub resource_uri : BallotDocEventUri,
+    pub doc          : DocumentUri,
+    pub by           : PersonUri,
+    #[serde(deserialize_with="deserialize_time")]
+    pub time         : DateTime<Utc>,
+    #[serde(rename = "type")]
+    pub event_type   : String,
+    pub rev          : Option<String>,
+    pub desc         : String,
+    // Fields specific to this type of event:
+    pub docevent_ptr : DocEventUri,
+    pub ballot_type  : BallotTypeUri
+}
+
+
+#[derive(Deserialize, Debug, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct NewRevisionDocEventUri(pub String);
+
+
+#[derive(Deserialize, Debug)]
+pub struct NewRevisionDocEvent {
+    // Fields common with BaseDocEvent:
+    pub id           : u64,
+    pub resource_uri : NewRevisionDocEventUri,
+    pub doc          : DocumentUri,
+    pub by           : PersonUri,
+    #[serde(deserialize_with="deserialize_time")]
+    pub time         : DateTime<Utc>,
+    #[serde(rename = "type")]
+    pub event_type   : String,
+    pub rev          : Option<String>,
+    pub desc         : String,
+    // Fields specific to this type of event:
+    pub docevent_ptr : DocEventUri
+}
+
+
+#[derive(Deserialize, Debug, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct SubmissionDocEventUri(pub String);
+
+
+#[derive(Deserialize, Debug)]
+pub struct SubmissionDocEvent {
+    // Fields common with BaseDocEvent:
+    pub id           : u64,
+    pub resource_uri : SubmissionDocEventUri,
+    pub doc          : DocumentUri,
+    pub by           : PersonUri,
+    #[serde(deserialize_with="deserialize_time")]
+    pub time         : DateTime<Utc>,
+    #[serde(rename = "type")]
+    pub event_type   : String,
+    pub rev          : Option<String>,
+    pub desc         : String,
+    // Fields specific to this type of event:
+    pub docevent_ptr : DocEventUri,
+    pub submission   : SubmissionUri
+}
+
+
+#[derive(Deserialize, Debug, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct WriteupDocEventUri(pub String);
+
+
+#[derive(Deserialize, Debug)]
+pub struct WriteupDocEvent {
+    // Fields common with BaseDocEvent:
+    pub id           : u64,
+    pub resource_uri : WriteupDocEventUri,
+    pub doc          : DocumentUri,
+    pub by           : PersonUri,
+    #[serde(deserialize_with="deserialize_time")]
+    pub time         : DateTime<Utc>,
+    #[serde(rename = "type")]
+    pub event_type   : String,
+    pub rev          : Option<String>,
+    pub desc         : String,
+    // Fields specific to this type of event:
+    pub docevent_ptr : DocEventUri,
+    pub text         : String
+}
+
+
+#[derive(Deserialize, Debug, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct ConsensusDocEventUri(pub String);
+
+
+#[derive(Deserialize, Debug)]
+pub struct ConsensusDocEvent {
+    // Fields common with BaseDocEvent:
+    pub id           : u64,
+    pub resource_uri : ConsensusDocEventUri,
+    pub doc          : DocumentUri,
+    pub by           : PersonUri,
+    #[serde(deserialize_with="deserialize_time")]
+    pub time         : DateTime<Utc>,
+    #[serde(rename = "type")]
+    pub event_type   : String,
+    pub rev          : Option<String>,
+    pub desc         : String,
+    // Fields specific to this type of event:
+    pub docevent_ptr : DocEventUri,
+    pub consensus    : Option<bool>
+}
+
+
+#[derive(Deserialize, Debug, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct BallotPositionDocEventUri(pub String);
+
+
+#[derive(Deserialize, Debug)]
+pub struct BallotPositionDocEvent {
+    // Fields common with BaseDocEvent:
+    pub id           : u64,
+    pub resource_uri : BallotPositionDocEventUri,
+    pub doc          : DocumentUri,
+    pub by           : PersonUri,
+    #[serde(deserialize_with="deserialize_time")]
+    pub time         : DateTime<Utc>,
+    #[serde(rename = "type")]
+    pub event_type   : String,
+    pub rev          : Option<String>,
+    pub desc         : String,
+    // Fields specific to this type of event:
+    pub docevent_ptr : DocEventUri,
+    pub ballot       : BallotDocEventUri,
+    pub balloter     : PersonUri,
+    pub pos          : BallotPosition,
+    pub discuss      : String,
+    #[serde(default, deserialize_with="deserialize_optional_time")]
+    pub discuss_time : Option<DateTime<Utc>>,
+    pub comment      : String,
+    #[serde(default, deserialize_with="deserialize_optional_time")]
+    pub comment_time : Option<DateTime<Utc>>,
+    pub send_email   : Option<bool>
+}
+
+
+#[derive(Deserialize, Debug, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct ReviewRequestDocEventUri(pub String);
+
+
+#[derive(Deserialize, Debug)]
+pub struct ReviewRequestDocEvent {
+    // Fields common with BaseDocEvent:
+    pub id             : u64,
+    pub resource_uri   : ReviewRequestDocEventUri,
+    pub doc            : DocumentUri,
+    pub by             : PersonUri,
+    #[serde(deserialize_with="deserialize_time")]
+    pub time           : DateTime<Utc>,
+    #[serde(rename = "type")]
+    pub event_type     : String,
+    pub rev            : Option<String>,
+    pub desc           : String,
+    // Fields specific to this type of event:
+    pub docevent_ptr   : DocEventUri,
+    pub review_request : ReviewRequestUri,
+    pub state          : ReviewRequestStateUri
+}
+
+
+#[derive(Deserialize, Debug, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct LastCallDocEventUri(pub String);
+
+
+#[derive(Deserialize, Debug)]
+pub struct LastCallDocEvent {
+    // Fields common with BaseDocEvent:
+    pub id           : u64,
+    pub resource_uri : LastCallDocEventUri,
+    pub doc          : DocumentUri,
+    pub by           : PersonUri,
+    #[serde(deserialize_with="deserialize_time")]
+    pub time         : DateTime<Utc>,
+    #[serde(rename = "type")]
+    pub event_type   : String,
+    pub rev          : Option<String>,
+    pub desc         : String,
+    // Fields specific to this type of event:
+    pub docevent_ptr : DocEventUri,
+    #[serde(default, deserialize_with="deserialize_optional_time")]
+    pub expires      : Option<DateTime<Utc>>
+}
+
+
+#[derive(Deserialize, Debug, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct TelechatDocEventUri(pub String);
+
+
+#[derive(Deserialize, Debug)]
+pub struct TelechatDocEvent {
+    // Fields common with BaseDocEvent:
+    pub id             : u64,
+    pub resource_uri   : TelechatDocEventUri,
+    pub doc            : DocumentUri,
+    pub by             : PersonUri,
+    #[serde(deserialize_with="deserialize_time")]
+    pub time           : DateTime<Utc>,
+    #[serde(rename = "type")]
+    pub event_type     : String,
+    pub rev            : Option<String>,
+    pub desc           : String,
+    // Fields specific to this type of event:
+    pub docevent_ptr   : DocEventUri,
+    #[serde(default, deserialize_with="deserialize_optional_date")]
+    pub telechat_date  : Option<NaiveDate>,
+    pub returning_item : bool
+}
+
+
+#[derive(Deserialize, Debug, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct EditedAuthorsDocEventUri(pub String);
+
+
+#[derive(Deserialize, Debug)]
+pub struct EditedAuthorsDocEvent {
+    // Fields common with BaseDocEvent:
+    pub id           : u64,
+    pub resource_uri : EditedAuthorsDocEventUri,
+    pub doc          : DocumentUri,
+    pub by           : PersonUri,
+    #[serde(deserialize_with="deserialize_time")]
+    pub time         : DateTime<Utc>,
+    #[serde(rename = "type")]
+    pub event_type   : String,
+    pub rev          : Option<String>,
+    pub desc         : String,
+    // Fields specific to this type of event:
+    pub docevent_ptr : DocEventUri,
+    pub basis        : String
+}
+
+
+#[derive(Deserialize, Debug, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct AddedMessageEventUri(pub String);
+
+
+#[derive(Deserialize, Debug)]
+pub struct AddedMessageEvent {
+    // Fields common with BaseDocEvent:
+    pub id           : u64,
+    pub resource_uri : AddedMessageEventUri,
+    pub doc          : DocumentUri,
+    pub by           : PersonUri,
+    #[serde(deserialize_with="deserialize_time")]
+    pub time         : DateTime<Utc>,
+    #[serde(rename = "type")]
+    pub event_type   : String,
+    pub rev          : Option<String>,
+    pub desc         : String,
+    // Fields specific to this type of event:
+    pub docevent_ptr : DocEventUri,
+    pub message      : Option<MessageUri>,
+    pub msgtype      : String,
+    pub in_reply_to  : Option<MessageUri>
+}
+
+// --------------------------------------------------------------------------------------------------------------------------------
+// A document event of any type:
+
+#[derive(Debug)]
+pub enum DocEvent {
+    Base(BaseDocEvent),
+    State(StateDocEvent),
+    Ballot(BallotDocEvent),
+    NewRevision(NewRevisionDocEvent),
+    Submission(SubmissionDocEvent),
+    Writeup(WriteupDocEvent),
+    Consensus(ConsensusDocEvent),
+    BallotPosition(BallotPositionDocEvent),
+    ReviewRequest(ReviewRequestDocEvent),
+    LastCall(LastCallDocEvent),
+    Telechat(TelechatDocEvent),
+    EditedAuthors(EditedAuthorsDocEvent),
+    AddedMessage(AddedMessageEvent)
+}
+
+
+// The values of the "type" field used by each of the more specific types of
+// event, following the Datatracker's doc/models.py. Events with other types
+// are only listed as base events.
+const DOC_EVENT_SUBTYPES : &[(&str, &[&str])] = &[
+    (StateDocEvent::PATH,          &["changed_state"]),
+    (BallotDocEvent::PATH,         &["created_ballot", "closed_ballot"]),
+    (NewRevisionDocEvent::PATH,    &["new_revision"]),
+    (SubmissionDocEvent::PATH,     &["new_submission"]),
+    (WriteupDocEvent::PATH,        &["changed_ballot_approval_text", "changed_ballot_writeup_text", "changed_ballot_rfceditornote_text",
+                                     "changed_last_call_text", "changed_action_announcement", "changed_review_announcement",
+                                     "changed_protocol_writeup"]),
+    (ConsensusDocEvent::PATH,      &["changed_consensus"]),
+    (BallotPositionDocEvent::PATH, &["changed_ballot_position"]),
+    (ReviewRequestDocEvent::PATH,  &["requested_review", "closed_review_request"]),
+    (LastCallDocEvent::PATH,       &["sent_last_call"]),
+    (TelechatDocEvent::PATH,       &["scheduled_for_telechat"]),
+    (EditedAuthorsDocEvent::PATH,  &["edited_authors"]),
+    (AddedMessageEvent::PATH,      &["added_message"])
+];
+
+
+macro_rules! doc_event_field {
+    ($event:expr, $e:ident => $field:expr) => {
+        match $event {
+            DocEvent::Base($e) => $field,
+            DocEvent::State($e) => $field,
+            DocEvent::Ballot($e) => $field,
+            DocEvent::NewRevision($e) => $field,
+            DocEvent::Submission($e) => $field,
+            DocEvent::Writeup($e) => $field,
+            DocEvent::Consensus($e) => $field,
+            DocEvent::BallotPosition($e) => $field,
+            DocEvent::ReviewRequest($e) => $field,
+            DocEvent::LastCall($e) => $field,
+            DocEvent::Telechat($e) => $field,
+            DocEvent::EditedAuthors($e) => $field,
+            DocEvent::AddedMessage($e) => $field,
+        }
+    }
+}
+
+
+impl DocEvent {
+    pub fn id(&self) -> u64 {
+        doc_event_field!(self, e => e.id)
+    }
+
+    pub fn doc(&self) -> &DocumentUri {
+        doc_event_field!(self, e => &e.doc)
+    }
+
+    pub fn by(&self) -> &PersonUri {
+        doc_event_field!(self, e => &e.by)
+    }
+
+    pub fn time(&self) -> DateTime<Utc> {
+        doc_event_field!(self, e => e.time)
+    }
+
+    // The type of event, e.g., "new_revision" or "changed_state"
+    pub fn event_type(&self) -> &str {
+        doc_event_field!(self, e => &e.event_type)
+    }
+
+    pub fn rev(&self) -> Option<&str> {
+        doc_event_field!(self, e => e.rev.as_deref())
+    }
+
+    pub fn desc(&self) -> &str {
+        doc_event_field!(self, e => &e.desc)
+    }
+
+    // The endpoints for the more specific types of event that are used by
+    // a list of events, so only those need to be fetched
+    pub(crate) fn subtype_paths(events : &[BaseDocEvent]) -> HashSet<&'static str> {
+        DOC_EVENT_SUBTYPES.iter()
+                          .filter(|(_, types)| events.iter().any(|e| types.contains(&e.event_type.as_str())))
+                          .map(|(path, _)| *path)
+                          .collect()
+    }
+
+    // Combine a list of events with the more specific versions of those
+    // events, keeping the order of the list. Events not in specific are
+    // returned as DocEvent::Base.
+    pub(crate) fn merge(events : Vec<BaseDocEvent>, specific : Vec<DocEvent>) -> Vec<DocEvent> {
+        let mut specific = specific.into_iter().map(|e| (e.id(), e)).collect::<HashMap<_, _>>();
+        events.into_iter().map(|e| specific.remove(&e.id).unwrap_or(DocEvent::Base(e))).collect()
+    }
+}
+
+// --------------------------------------------------------------------------------------------------------------------------------
+// Deleted objects. These are listed with the document events, but are not
+// document events, and can record the deletion of any type of object.
+
+#[derive(Deserialize, Debug, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct DeletedEventUri(pub String);
+
+
+#[derive(Deserialize, Debug)]
+pub struct DeletedEvent {
+    pub id           : u64,
+    pub resource_uri : DeletedEventUri,
+    pub content_type : ContentTypeUri,
+    pub json         : String,
+    pub by           : PersonUri,
+    #[serde(deserialize_with="deserialize_time")]
+    pub time         : DateTime<Utc>
+}
+
+// --------------------------------------------------------------------------------------------------------------------------------
@@ -28,15 +28,17 @@
 use chrono::prelude::*;
 use serde::Deserialize;
 
-use super::deserialize_time;
+use super::{deserialize_time, deserialize_optional_time};
 use super::email::EmailUri;
 use super::person::PersonUri;
 use super::group::GroupUri;
+use super::name::*;
+use super::submission::SubmissionUri;
 
 // --------------------------------------------------------------------------------------------------------------------------------
 // Types relating to documents:
 
-#[derive(Deserialize, Debug, Eq, PartialEq)]
+#[derive(Deserialize, Debug, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
 pub struct DocumentUri(pub String);
 
 
@@ -51,10 +53,10 @@ pub struct Document {
     #[serde(deserialize_with="deserialize_time")]
     pub time               : DateTime<Utc>,
     pub notify             : String,
-    #[serde(deserialize_with="deserialize_time")]
-    pub expires            : DateTime<Utc>,
+    #[serde(default, deserialize_with="deserialize_optional_time")]
+    pub expires            : Option<DateTime<Utc>>,
     #[serde(rename = "type")]
-    pub doc_type           : String,            // FIXME
+    pub doc_type           : DocType,
     pub rfc                : Option<u64>,
     pub rev                : String,
     #[serde(rename = "abstract")]
@@ -65,28 +67,79 @@ pub struct Document {
     pub ad                 : Option<PersonUri>,
     pub shepherd           : Option<EmailUri>,
     pub group              : Option<GroupUri>,
-    pub stream             : Option<String>,    // FIXME
-    pub std_level          : Option<String>,    // FIXME
-    pub intended_std_level : Option<String>,    // FIXME
+    pub stream             : Option<DocStream>,
+    pub std_level          : Option<StdLevel>,
+    pub intended_std_level : Option<IntendedStdLevel>,
     pub states             : Vec<DocStateUri>,
     pub submissions        : Vec<SubmissionUri>,
-    pub tags               : Vec<String>,
+    pub tags               : Vec<DocTag>,
     pub uploaded_filename  : String,
     pub external_url       : String
 }
 
 
-#[derive(Deserialize, Debug, Eq, PartialEq)]
-pub struct SubmissionUri(pub String);
+#[derive(Deserialize, Debug, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct DocAliasUri(pub String);
+
+
+// An alternative name for a document. Drafts that are published as an RFC
+// have an alias "rfcNNNN", and RFCs that are part of a subseries have an
+// alias naming the subseries, e.g., "bcp14" or "std7". A subseries name is
+// an alias of each document in the subseries.
+#[derive(Deserialize, Debug)]
+pub struct DocAlias {
+    pub id           : u64,
+    pub resource_uri : DocAliasUri,
+    pub document     : DocumentUri,
+    pub name         : String
+}
+
+
+#[derive(Deserialize, Debug, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct DocumentAuthorUri(pub String);
 
 
+// An author of a document. The order gives the position of the author
+// in the list of authors, starting from 1.
 #[derive(Deserialize, Debug)]
-pub struct Submission {
-    // FIXME
+pub struct DocumentAuthor {
+    pub id           : u64,
+    pub resource_uri : DocumentAuthorUri,
+    pub document     : DocumentUri,
+    pub person       : PersonUri,
+    pub email        : Option<EmailUri>,
+    pub affiliation  : String,
+    pub country      : String,
+    pub order        : u64
+}
+
+
+#[derive(Deserialize, Debug, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct RelatedDocumentUri(pub String);
+
+
+// A relationship from the source document to the target, e.g., the source
+// replaces, obsoletes, or references the target. The target is an alias,
+// such as "rfc3550", rather than a document.
+#[derive(Deserialize, Debug)]
+pub struct RelatedDocument {
+    pub id           : u64,
+    pub resource_uri : RelatedDocumentUri,
+    pub source       : DocumentUri,
+    pub target       : DocAliasUri,
+    pub relationship : Relationship
+}
+
+
+// The direction in which to follow relationships between documents
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum RelationshipDirection {
+    Outgoing,   // From the source to the target
+    Incoming    // From the target to the source
 }
 
 
-#[derive(Deserialize, Debug, Eq, PartialEq)]
+#[derive(Deserialize, Debug, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
 pub struct DocStateUri(pub String);
 
 
@@ -105,7 +158,7 @@ pub struct DocState {
 }
 
 
-#[derive(Deserialize, Debug, Eq, PartialEq)]
+#[derive(Deserialize, Debug, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
 pub struct DocStateTypeUri(pub String);
 
 
@@ -34,7 +34,7 @@ use super::person::PersonUri;
 // --------------------------------------------------------------------------------------------------------------------------------
 // Types relating to email addresses:
 
-#[derive(Deserialize, Debug, Eq, PartialEq)]
+#[derive(Deserialize, Debug, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
 pub struct EmailUri(pub String);
 
 
@@ -51,7 +51,7 @@ pub struct Email {
 }
 
 
-#[derive(Deserialize, Debug, Eq, PartialEq)]
+#[derive(Deserialize, Debug, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
 pub struct HistoricalEmailUri(pub String);
 
 
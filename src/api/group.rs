@@ -29,15 +29,17 @@ use chrono::prelude::*;
 use serde::Deserialize;
 
 use super::deserialize_time;
+use super::email::EmailUri;
 use super::person::PersonUri;
 use super::document::DocumentUri;
 use super::document::DocStateUri;
+use super::name::RoleName;
 
 // --------------------------------------------------------------------------------------------------------------------------------
 // Types relating to groups:
 
-#[derive(Deserialize, Debug, Eq, PartialEq)]
-pub struct GroupUri(String);
+#[derive(Deserialize, Debug, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct GroupUri(pub String);
 
 #[derive(Deserialize, Debug)]
 pub struct Group {
@@ -46,14 +48,15 @@ pub struct Group {
     pub acronym        : String,
     pub name           : String,
     pub description    : String,
-    pub charter        : DocumentUri,
+    pub charter        : Option<DocumentUri>,
+    #[serde(default)]
     pub ad             : Option<PersonUri>,
     #[serde(deserialize_with="deserialize_time")]
     pub time           : DateTime<Utc>,
     #[serde(rename = "type")]
     pub group_type     : GroupTypeUri,
     pub comments       : String,
-    pub parent         : GroupUri,
+    pub parent         : Option<GroupUri>,
     pub state          : GroupStateUri,
     pub unused_states  : Vec<DocStateUri>,
     pub unused_tags    : Vec<String>,
@@ -63,12 +66,12 @@ pub struct Group {
 }
 
 
-#[derive(Deserialize, Debug, Eq, PartialEq)]
-pub struct GroupTypeUri(String);
+#[derive(Deserialize, Debug, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct GroupTypeUri(pub String);
 
 
 #[derive(Deserialize, Debug)]
-struct GroupType {
+pub struct GroupType {
     pub resource_uri : GroupTypeUri,
     pub name         : String,
     pub verbose_name : String,
@@ -79,7 +82,7 @@ struct GroupType {
 }
 
 
-#[derive(Deserialize, Debug, Eq, PartialEq)]
+#[derive(Deserialize, Debug, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
 pub struct GroupStateUri(pub String);
 
 
@@ -93,4 +96,98 @@ pub struct GroupState {
     pub order        : u64
 }
 
+
+
+#[derive(Deserialize, Debug, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct GroupHistoryUri(pub String);
+
+
+// A snapshot of a group, saved before the group was changed. The snapshot
+// describes the group from its time until the time of the next snapshot,
+// or the time of the group if there are no later snapshots.
+#[derive(Deserialize, Debug)]
+pub struct GroupHistory {
+    pub id             : u64,
+    pub resource_uri   : GroupHistoryUri,
+    pub group          : GroupUri,
+    pub acronym        : String,
+    pub name           : String,
+    pub description    : String,
+    #[serde(default)]
+    pub ad             : Option<PersonUri>,
+    #[serde(deserialize_with="deserialize_time")]
+    pub time           : DateTime<Utc>,
+    #[serde(rename = "type")]
+    pub group_type     : GroupTypeUri,
+    pub comments       : String,
+    pub parent         : Option<GroupUri>,
+    pub state          : GroupStateUri,
+    pub unused_states  : Vec<DocStateUri>,
+    pub unused_tags    : Vec<String>,
+    pub list_email     : String,
+    pub list_subscribe : String,
+    pub list_archive   : String
+}
+
+
+impl GroupHistory {
+    // The group as it was described by this snapshot. The charter is taken
+    // from the current group, since it is not recorded in the snapshot.
+    pub fn into_group(self, current : &Group) -> Group {
+        Group {
+            id             : current.id,
+            resource_uri   : self.group,
+            acronym        : self.acronym,
+            name           : self.name,
+            description    : self.description,
+            charter        : current.charter.clone(),
+            ad             : self.ad,
+            time           : self.time,
+            group_type     : self.group_type,
+            comments       : self.comments,
+            parent         : self.parent,
+            state          : self.state,
+            unused_states  : self.unused_states,
+            unused_tags    : self.unused_tags,
+            list_email     : self.list_email,
+            list_subscribe : self.list_subscribe,
+            list_archive   : self.list_archive
+        }
+    }
+}
+
+// --------------------------------------------------------------------------------------------------------------------------------
+// Types relating to roles that people have in groups:
+
+#[derive(Deserialize, Debug, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct RoleUri(pub String);
+
+
+// A role that a person currently has in a group, e.g., chair
+#[derive(Deserialize, Debug)]
+pub struct Role {
+    pub id           : u64,
+    pub resource_uri : RoleUri,
+    pub name         : RoleName,
+    pub group        : GroupUri,
+    pub person       : PersonUri,
+    pub email        : EmailUri
+}
+
+
+#[derive(Deserialize, Debug, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct RoleHistoryUri(pub String);
+
+
+// A role that a person had in a group, as recorded in a GroupHistory
+#[derive(Deserialize, Debug)]
+pub struct RoleHistory {
+    pub id           : u64,
+    pub resource_uri : RoleHistoryUri,
+    pub name         : RoleName,
+    pub group        : GroupHistoryUri,
+    pub person       : PersonUri,
+    pub email        : EmailUri
+}
+
 // --------------------------------------------------------------------------------------------------------------------------------
@@ -0,0 +1,158 @@
+// Copyright (C) 2019 University of Glasgow
+// 
+// Redistribution and use in source and binary forms, with or without
+// modification, are permitted provided that the following conditions 
+// are met:
+// 
+// 1. Redistributions of source code must retain the above copyright notice,
+//    this list of conditions and the following disclaimer.
+// 
+// 2. Redistributions in binary form must reproduce the above copyright
+//    notice, this list of conditions and the following disclaimer in the
+//    documentation and/or other materials provided with the distribution.
+// 
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+// AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+// IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE
+// ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE
+// LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR
+// CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF
+// SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS
+// INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN
+// CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE)
+// ARISING IN ANY WAY OUT OF THE USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE
+// POSSIBILITY OF SUCH DAMAGE.
+//
+// SPDX-License-Identifier: BSD-2-Clause
+
+
+// Events in the history of a group. Every event is a GroupEvent, and some
+// are also one of the more specific types of event, that are listed at
+// their own endpoints and have additional fields. GroupEvent::Base is used
+// for events that aren't any of the more specific types.
+
+use std::collections::{HashMap, HashSet};
+
+use chrono::prelude::*;
+use serde::Deserialize;
+
+use super::deserialize_time;
+use super::group::{GroupUri, GroupStateUri};
+use super::milestone::MilestoneGroupEvent;
+use super::person::PersonUri;
+use crate::resource::Endpoint;
+
+// --------------------------------------------------------------------------------------------------------------------------------
+// Types relating to group events:
+
+#[derive(Deserialize, Debug, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct GroupEventUri(pub String);
+
+
+// The fields common to all group events
+#[derive(Deserialize, Debug)]
+pub struct BaseGroupEvent {
+    pub id           : u64,
+    pub resource_uri : GroupEventUri,
+    pub group        : GroupUri,
+    #[serde(deserialize_with="deserialize_time")]
+    pub time         : DateTime<Utc>,
+    #[serde(rename = "type")]
+    pub event_type   : String,
+    pub by           : PersonUri,
+    pub desc         : String
+}
+
+
+#[derive(Deserialize, Debug, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct ChangeStateGroupEventUri(pub String);
+
+
+#[derive(Deserialize, Debug)]
+pub struct ChangeStateGroupEvent {
+    // Fields common with BaseGroupEvent:
+    pub id             : u64,
+    pub resource_uri   : ChangeStateGroupEventUri,
+    pub group          : GroupUri,
+    #[serde(deserialize_with="deserialize_time")]
+    pub time           : DateTime<Utc>,
+    #[serde(rename = "type")]
+    pub event_type     : String,
+    pub by             : PersonUri,
+    pub desc           : String,
+    // Fields specific to this type of event:
+    pub groupevent_ptr : GroupEventUri,
+    pub state          : GroupStateUri
+}
+
+// --------------------------------------------------------------------------------------------------------------------------------
+// A group event of any type:
+
+#[derive(Debug)]
+pub enum GroupEvent {
+    Base(BaseGroupEvent),
+    ChangeState(ChangeStateGroupEvent),
+    Milestone(MilestoneGroupEvent)
+}
+
+
+// As DOC_EVENT_SUBTYPES, following the Datatracker's group/models.py
+const GROUP_EVENT_SUBTYPES : &[(&str, &[&str])] = &[
+    (ChangeStateGroupEvent::PATH, &["changed_state"]),
+    (MilestoneGroupEvent::PATH,   &["changed_milestone"])
+];
+
+
+macro_rules! group_event_field {
+    ($event:expr, $e:ident => $field:expr) => {
+        match $event {
+            GroupEvent::Base($e) => $field,
+            GroupEvent::ChangeState($e) => $field,
+            GroupEvent::Milestone($e) => $field,
+        }
+    }
+}
+
+
+impl GroupEvent {
+    pub fn id(&self) -> u64 {
+        group_event_field!(self, e => e.id)
+    }
+
+    pub fn group(&self) -> &GroupUri {
+        group_event_field!(self, e => &e.group)
+    }
+
+    pub fn by(&self) -> &PersonUri {
+        group_event_field!(self, e => &e.by)
+    }
+
+    pub fn time(&self) -> DateTime<Utc> {
+        group_event_field!(self, e => e.time)
+    }
+
+    // The type of event, e.g., "changed_state" or "info_changed"
+    pub fn event_type(&self) -> &str {
+        group_event_field!(self, e => &e.event_type)
+    }
+
+    pub fn desc(&self) -> &str {
+        group_event_field!(self, e => &e.desc)
+    }
+
+    // As DocEvent::subtype_paths()
+    pub(crate) fn subtype_paths(events : &[BaseGroupEvent]) -> HashSet<&'static str> {
+        GROUP_EVENT_SUBTYPES.iter()
+                            .filter(|(_, types)| events.iter().any(|e| types.contains(&e.event_type.as_str())))
+                            .map(|(path, _)| *path)
+                            .collect()
+    }
+
+    // As DocEvent::merge()
+    pub(crate) fn merge(events : Vec<BaseGroupEvent>, specific : Vec<GroupEvent>) -> Vec<GroupEvent> {
+        let mut specific = specific.into_iter().map(|e| (e.id(), e)).collect::<HashMap<_, _>>();
+        events.into_iter().map(|e| specific.remove(&e.id).unwrap_or(GroupEvent::Base(e))).collect()
+    }
+}
+
+// --------------------------------------------------------------------------------------------------------------------------------
@@ -0,0 +1,140 @@
+// Copyright (C) 2019 University of Glasgow
+// 
+// Redistribution and use in source and binary forms, with or without
+// modification, are permitted provided that the following conditions 
+// are met:
+// 
+// 1. Redistributions of source code must retain the above copyright notice,
+//    this list of conditions and the following disclaimer.
+// 
+// 2. Redistributions in binary form must reproduce the above copyright
+//    notice, this list of conditions and the following disclaimer in the
+//    documentation and/or other materials provided with the distribution.
+// 
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+// AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+// IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE
+// ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE
+// LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR
+// CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF
+// SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS
+// INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN
+// CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE)
+// ARISING IN ANY WAY OUT OF THE USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE
+// POSSIBILITY OF SUCH DAMAGE.
+//
+// SPDX-License-Identifier: BSD-2-Clause
+
+
+// Group milestones. When a milestone is changed, the Datatracker saves its
+// previous version as a GroupMilestoneHistory, and records the change as a
+// MilestoneGroupEvent.
+
+use chrono::prelude::*;
+use serde::Deserialize;
+
+use super::{deserialize_time, deserialize_date};
+use super::document::DocumentUri;
+use super::group::GroupUri;
+use super::groupevent::GroupEventUri;
+use super::name::GroupMilestoneState;
+use super::person::PersonUri;
+
+// --------------------------------------------------------------------------------------------------------------------------------
+// Types relating to group milestones:
+
+#[derive(Deserialize, Debug, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct GroupMilestoneUri(pub String);
+
+
+#[derive(Deserialize, Debug)]
+pub struct GroupMilestone {
+    pub id           : u64,
+    pub resource_uri : GroupMilestoneUri,
+    pub group        : GroupUri,
+    pub state        : GroupMilestoneState,
+    pub desc         : String,
+    #[serde(deserialize_with="deserialize_date")]
+    pub due          : NaiveDate,
+    pub order        : Option<u64>,
+    pub resolved     : String,
+    pub docs         : Vec<DocumentUri>,
+    #[serde(deserialize_with="deserialize_time")]
+    pub time         : DateTime<Utc>
+}
+
+
+impl GroupMilestone {
+    // An active milestone that is unresolved and was due before the given date
+    pub fn is_overdue(&self, date : NaiveDate) -> bool {
+        self.state == GroupMilestoneState::Active && self.resolved.is_empty() && self.due < date
+    }
+
+    // The due dates of the milestone, oldest first, with the time that each
+    // was set, given the history of the milestone
+    pub fn due_dates(&self, history : &[GroupMilestoneHistory]) -> Vec<(DateTime<Utc>, NaiveDate)> {
+        let mut versions = history.iter().map(|h| (h.time, h.due)).collect::<Vec<_>>();
+        versions.sort();
+        versions.push((self.time, self.due));
+        versions.dedup_by(|later, earlier| later.1 == earlier.1);
+        versions
+    }
+}
+
+
+#[derive(Deserialize, Debug, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct GroupMilestoneHistoryUri(pub String);
+
+
+// A previous version of a milestone, as it was from its time until the
+// time of the next version
+#[derive(Deserialize, Debug)]
+pub struct GroupMilestoneHistory {
+    pub id           : u64,
+    pub resource_uri : GroupMilestoneHistoryUri,
+    pub milestone    : GroupMilestoneUri,
+    pub group        : GroupUri,
+    pub state        : GroupMilestoneState,
+    pub desc         : String,
+    #[serde(deserialize_with="deserialize_date")]
+    pub due          : NaiveDate,
+    pub order        : Option<u64>,
+    pub resolved     : String,
+    pub docs         : Vec<DocumentUri>,
+    #[serde(deserialize_with="deserialize_time")]
+    pub time         : DateTime<Utc>
+}
+
+
+// An entry in the table of milestone states
+#[derive(Deserialize, Debug)]
+pub struct GroupMilestoneStateName {
+    pub resource_uri : GroupMilestoneState,
+    pub slug         : String,
+    pub name         : String,
+    pub desc         : String,
+    pub used         : bool,
+    pub order        : u64
+}
+
+
+#[derive(Deserialize, Debug, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct MilestoneGroupEventUri(pub String);
+
+
+#[derive(Deserialize, Debug)]
+pub struct MilestoneGroupEvent {
+    pub id             : u64,
+    pub resource_uri   : MilestoneGroupEventUri,
+    pub group          : GroupUri,
+    #[serde(deserialize_with="deserialize_time")]
+    pub time           : DateTime<Utc>,
+    #[serde(rename = "type")]
+    pub event_type     : String,
+    pub by             : PersonUri,
+    pub desc           : String,
+    pub groupevent_ptr : GroupEventUri,
+    pub milestone      : GroupMilestoneUri
+}
+
+// --------------------------------------------------------------------------------------------------------------------------------
@@ -29,9 +29,18 @@ pub mod email;
 pub mod person;
 pub mod group;
 pub mod document;
+pub mod docevent;
+pub mod name;
+pub mod submission;
+pub mod ballot;
+pub mod milestone;
+pub mod groupevent;
 
 use std::error;
 use std::fmt;
+use std::io;
+use std::path::PathBuf;
+use std::time::Duration;
 
 use chrono::prelude::*;
 use serde::{Deserialize, Deserializer};
@@ -42,7 +51,32 @@ pub fn deserialize_time<'de, D>(deserializer: D) -> Result<DateTime<Utc>, D::Err
     where D: Deserializer<'de>
 {
     let s = String::deserialize(deserializer)?;
-    Utc.datetime_from_str(&s, "%+").map_err(serde::de::Error::custom)
+    DateTime::parse_from_str(&s, "%+").map(|t| t.with_timezone(&Utc)).map_err(serde::de::Error::custom)
+}
+
+pub fn deserialize_date<'de, D>(deserializer: D) -> Result<NaiveDate, D::Error>
+    where D: Deserializer<'de>
+{
+    let s = String::deserialize(deserializer)?;
+    NaiveDate::parse_from_str(&s, "%Y-%m-%d").map_err(serde::de::Error::custom)
+}
+
+pub fn deserialize_optional_time<'de, D>(deserializer: D) -> Result<Option<DateTime<Utc>>, D::Error>
+    where D: Deserializer<'de>
+{
+    match Option::<String>::deserialize(deserializer)? {
+        Some(s) => DateTime::parse_from_str(&s, "%+").map(|t| Some(t.with_timezone(&Utc))).map_err(serde::de::Error::custom),
+        None    => Ok(None)
+    }
+}
+
+pub fn deserialize_optional_date<'de, D>(deserializer: D) -> Result<Option<NaiveDate>, D::Error>
+    where D: Deserializer<'de>
+{
+    match Option::<String>::deserialize(deserializer)? {
+        Some(s) => NaiveDate::parse_from_str(&s, "%Y-%m-%d").map(Some).map_err(serde::de::Error::custom),
+        None    => Ok(None)
+    }
 }
 
 // =================================================================================================
@@ -63,39 +97,64 @@ pub struct Page<T> {
     pub objects     : Vec<T>
 }
 
+// A list of objects fetched one page at a time. The list tracks the offset
+// of the next object it will return, so an interrupted crawl can be resumed
+// using Query::offset(), provided the query has a stable order_by().
 pub struct PaginatedList<'a, T> {
-    pub iter : <Vec<T> as IntoIterator>::IntoIter,
-    pub next : Option<String>,
-    pub conn : &'a reqwest::Client
+    pub iter    : <Vec<T> as IntoIterator>::IntoIter,
+    pub next    : Option<String>,
+    pub dt      : &'a crate::Datatracker,
+    total_count : u32,
+    limit       : u32,
+    offset      : u32
 }
 
 impl<'a, T> PaginatedList<'a, T>
     where for<'de> T: Deserialize<'de>
 {
-    pub fn new(conn: &'a reqwest::Client, url : String) -> Result<Self, DatatrackerError> {
-        let mut res = conn.get(&url).send()?;
-        let pl : Page<T> = res.json()?;
+    // The url is the path and query of the first page, relative to the
+    // base URL of the Datatracker, e.g., "/api/v1/person/person/"
+    pub fn new(dt: &'a crate::Datatracker, url : String) -> Result<Self, DatatrackerError> {
+        let pl : Page<T> = dt.retrieve(&url)?;
 
         Ok(Self {
-            next : pl.meta.next.clone(),
-            iter : pl.objects.into_iter(),
-            conn : conn
+            next        : pl.meta.next.clone(),
+            total_count : pl.meta.total_count,
+            limit       : pl.meta.limit,
+            offset      : pl.meta.offset,
+            iter        : pl.objects.into_iter(),
+            dt
         })
     }
 
+    // A list with no objects, for queries that cannot match anything and
+    // so need not be sent to the Datatracker
+    pub fn empty(dt: &'a crate::Datatracker) -> Self {
+        Self {
+            iter        : Vec::new().into_iter(),
+            next        : None,
+            total_count : 0,
+            limit       : 0,
+            offset      : 0,
+            dt
+        }
+    }
+
     fn try_next(&mut self) -> Result<Option<T>, DatatrackerError> {
         match self.iter.next() {
             Some(x) => {
+                self.offset += 1;
                 Ok(Some(x))
             }
             None => {
                 match self.next.clone() {
                     Some(ref url_frag) => {
-                        let url = format!("https://datatracker.ietf.org{}", url_frag);
-                        let mut res = self.conn.get(&url).send()?;
-                        let pl : Page<T> = res.json()?;
-                        self.next = pl.meta.next.clone();
-                        self.iter = pl.objects.into_iter();
+                        let pl : Page<T> = self.dt.retrieve(url_frag)?;
+                        self.next        = pl.meta.next.clone();
+                        self.total_count = pl.meta.total_count;
+                        self.limit       = pl.meta.limit;
+                        self.offset      = pl.meta.offset;
+                        self.iter        = pl.objects.into_iter();
                         self.try_next()
                     }
                     None => {
@@ -107,6 +166,25 @@ impl<'a, T> PaginatedList<'a, T>
     }
 }
 
+impl<'a, T> PaginatedList<'a, T> {
+    // The total number of objects in the list, as reported by the
+    // Datatracker when the most recent page was fetched
+    pub fn total_count(&self) -> u32 {
+        self.total_count
+    }
+
+    // The number of objects fetched in each page
+    pub fn limit(&self) -> u32 {
+        self.limit
+    }
+
+    // The offset of the next object to be returned. Save this to checkpoint
+    // a crawl, and pass it to Query::offset() to resume.
+    pub fn offset(&self) -> u32 {
+        self.offset
+    }
+}
+
 impl<'a, T> Iterator for PaginatedList<'a, T>
     where for<'de> T: Deserialize<'de>
 {
@@ -119,6 +197,23 @@ impl<'a, T> Iterator for PaginatedList<'a, T>
             Err(e)      => Some(Err(e))
         }
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = remaining(self.iter.len(), self.next.is_some(), self.total_count, self.offset);
+        (remaining, None)
+    }
+}
+
+
+// The number of objects remaining in a paginated list, given the number left
+// in the current page. This is only an estimate, since the list can change on
+// the server while it is being fetched, so size_hint() gives no upper bound.
+pub(crate) fn remaining(in_page : usize, has_next : bool, total_count : u32, offset : u32) -> usize {
+    if has_next {
+        in_page.max(total_count.saturating_sub(offset) as usize)
+    } else {
+        in_page
+    }
 }
 
 
@@ -128,6 +223,26 @@ impl<'a, T> Iterator for PaginatedList<'a, T>
 #[derive(Debug)]
 pub enum DatatrackerError {
     NotFound,
+    HttpError {
+        url         : String,
+        status      : u16
+    },
+    RateLimited {
+        url         : String,
+        retry_after : Option<Duration>
+    },
+    DeserializationError {
+        url         : String,
+        path        : String,       // Path to the field that failed, e.g., "objects[3].time"
+        snippet     : String,       // The part of the response body where the error occurred
+        error       : serde_json::Error
+    },
+    InvalidUri(String),
+    InvalidUserAgent(String),
+    InvalidRateLimit(f64),
+    FixtureError(PathBuf, io::Error),
+    CacheError(PathBuf, io::Error),
+    NotInCache(String),
     IoError(reqwest::Error)
 }
 
@@ -136,6 +251,17 @@ impl fmt::Display for DatatrackerError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
             DatatrackerError::NotFound => write!(f, "Not found"),
+            DatatrackerError::HttpError{ref url, status} => write!(f, "HTTP error {}: {}", status, url),
+            DatatrackerError::RateLimited{ref url, retry_after : Some(d)} => write!(f, "Rate limited, retry after {}s: {}", d.as_secs(), url),
+            DatatrackerError::RateLimited{ref url, retry_after : None} => write!(f, "Rate limited: {}", url),
+            DatatrackerError::DeserializationError{ref url, ref path, ref snippet, ref error} =>
+                write!(f, "Cannot parse response from {} at {}: {} (near \"{}\")", url, path, error, snippet),
+            DatatrackerError::InvalidUri(ref uri) => write!(f, "Invalid URI: {}", uri),
+            DatatrackerError::InvalidUserAgent(ref ua) => write!(f, "Invalid user agent: {}", ua),
+            DatatrackerError::InvalidRateLimit(rate) => write!(f, "Invalid rate limit: {} requests per second", rate),
+            DatatrackerError::FixtureError(ref path, ref e) => write!(f, "{}: {}", path.display(), e),
+            DatatrackerError::CacheError(ref path, ref e) => write!(f, "{}: {}", path.display(), e),
+            DatatrackerError::NotInCache(ref url) => write!(f, "Not in cache: {}", url),
             DatatrackerError::IoError(ref e) => e.fmt(f)
         }
     }
@@ -146,6 +272,15 @@ impl error::Error for DatatrackerError {
     fn source(&self) -> Option<&(dyn error::Error + 'static)> {
         match *self {
             DatatrackerError::NotFound => None,
+            DatatrackerError::HttpError{..} => None,
+            DatatrackerError::RateLimited{..} => None,
+            DatatrackerError::DeserializationError{ref error, ..} => Some(error),
+            DatatrackerError::InvalidUri(_) => None,
+            DatatrackerError::InvalidUserAgent(_) => None,
+            DatatrackerError::InvalidRateLimit(_) => None,
+            DatatrackerError::FixtureError(_, ref e) => Some(e),
+            DatatrackerError::CacheError(_, ref e) => Some(e),
+            DatatrackerError::NotInCache(_) => None,
             DatatrackerError::IoError(ref e) => Some(e)
         }
     }
@@ -160,4 +295,44 @@ impl From<reqwest::Error> for DatatrackerError {
 
 pub type DTResult<T> = Result<T, DatatrackerError>;
 
+
+// Up to 80 characters of the body either side of the given line and column
+fn snippet(body : &str, line : usize, column : usize) -> String {
+    let offset = body.split('\n').take(line.saturating_sub(1)).map(|l| l.len() + 1).sum::<usize>() + column;
+    let mut start = offset.saturating_sub(80).min(body.len());
+    let mut end   = offset.saturating_add(80).min(body.len());
+    while !body.is_char_boundary(start) {
+        start -= 1;
+    }
+    while !body.is_char_boundary(end) {
+        end += 1;
+    }
+    body[start..end].to_string()
+}
+
+
+// Decode a response received from the Datatracker, or replayed from a fixture
+pub(crate) fn decode_response<T>(url : &str, status : u16, body : &str) -> DTResult<T>
+    where for<'de> T: Deserialize<'de>
+{
+    match status {
+        200..=299 => {
+            let de = &mut serde_json::Deserializer::from_str(body);
+            serde_path_to_error::deserialize(de).map_err(|e| {
+                let path  = e.path().to_string();
+                let error = e.into_inner();
+                DatatrackerError::DeserializationError {
+                    url     : url.to_string(),
+                    path,
+                    snippet : snippet(body, error.line(), error.column()),
+                    error
+                }
+            })
+        }
+        404 | 410 => Err(DatatrackerError::NotFound),
+        429       => Err(DatatrackerError::RateLimited { url : url.to_string(), retry_after : None }),
+        _         => Err(DatatrackerError::HttpError { url : url.to_string(), status })
+    }
+}
+
 // =================================================================================================
@@ -0,0 +1,291 @@
+// Copyright (C) 2019 University of Glasgow
+// 
+// Redistribution and use in source and binary forms, with or without
+// modification, are permitted provided that the following conditions 
+// are met:
+// 
+// 1. Redistributions of source code must retain the above copyright notice,
+//    this list of conditions and the following disclaimer.
+// 
+// 2. Redistributions in binary form must reproduce the above copyright
+//    notice, this list of conditions and the following disclaimer in the
+//    documentation and/or other materials provided with the distribution.
+// 
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+// AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+// IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE
+// ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE
+// LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR
+// CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF
+// SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS
+// INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN
+// CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE)
+// ARISING IN ANY WAY OUT OF THE USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE
+// POSSIBILITY OF SUCH DAMAGE.
+//
+// SPDX-License-Identifier: BSD-2-Clause
+
+
+// The Datatracker has many tables of names, such as the types of document
+// and the types of relationship between documents. Other objects refer to
+// a name by the URI of its entry in the table, for example:
+//   /api/v1/name/docrelationshipname/replaces/
+// These types represent such URIs as enums, so they can be matched on. The
+// Unknown variant holds the slug of any name not known to this library, so
+// that names added to the Datatracker don't cause deserialization to fail.
+
+use std::fmt;
+
+use serde::{Deserialize, Deserializer};
+use serde::de::Error;
+
+// --------------------------------------------------------------------------------------------------------------------------------
+
+macro_rules! name_enum {
+    ($name:ident, $path:expr, { $($variant:ident => $slug:expr),* $(,)? }) => {
+        #[derive(Debug, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+        pub enum $name {
+            $($variant,)*
+            Unknown(String)
+        }
+
+        impl $name {
+            pub const PATH : &'static str = $path;
+
+            pub fn from_slug(slug : &str) -> Self {
+                match slug {
+                    $($slug => $name::$variant,)*
+                    _ => $name::Unknown(slug.to_string())
+                }
+            }
+
+            pub fn slug(&self) -> &str {
+                match self {
+                    $($name::$variant => $slug,)*
+                    $name::Unknown(slug) => slug
+                }
+            }
+
+            // The URI of the name, e.g., "/api/v1/name/doctypename/draft/"
+            pub fn uri(&self) -> String {
+                format!("{}{}/", $path, self.slug())
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D>(deserializer : D) -> Result<Self, D::Error>
+                where D: Deserializer<'de>
+            {
+                let uri = String::deserialize(deserializer)?;
+                match uri.strip_prefix($path).and_then(|s| s.strip_suffix('/')) {
+                    Some(slug) => Ok($name::from_slug(slug)),
+                    None       => Err(D::Error::custom(format!("invalid {} URI: {}", stringify!($name), uri)))
+                }
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "{}", self.slug())
+            }
+        }
+
+        impl crate::QueryValue for $name {
+            fn to_query_value(&self) -> String {
+                self.slug().to_string()
+            }
+        }
+    }
+}
+
+// --------------------------------------------------------------------------------------------------------------------------------
+// Names used by documents:
+
+name_enum!(DocType, "/api/v1/name/doctypename/", {
+    Agenda               => "agenda",
+    Bluesheets           => "bluesheets",
+    BofRequest           => "bofreq",
+    Charter              => "charter",
+    ConflictReview       => "conflrev",
+    Draft                => "draft",
+    Liaison              => "liaison",
+    LiaisonAttachment    => "liai-att",
+    Minutes              => "minutes",
+    ProceedingsMaterials => "procmaterials",
+    Recording            => "recording",
+    Review               => "review",
+    Rfc                  => "rfc",
+    ShepherdWriteup      => "shepwrit",
+    Slides               => "slides",
+    StatusChange         => "statchg",
+});
+
+
+name_enum!(DocStream, "/api/v1/name/streamname/", {
+    Ietf      => "ietf",
+    Irtf      => "irtf",
+    Iab       => "iab",
+    Ise       => "ise",
+    Editorial => "editorial",
+    Legacy    => "legacy",
+});
+
+
+// The "unkn" level is UnknownLevel, since Unknown holds unrecognised slugs
+name_enum!(StdLevel, "/api/v1/name/stdlevelname/", {
+    InternetStandard    => "std",
+    DraftStandard       => "ds",
+    ProposedStandard    => "ps",
+    Informational       => "inf",
+    Experimental        => "exp",
+    BestCurrentPractice => "bcp",
+    Historic            => "hist",
+    UnknownLevel        => "unkn",
+});
+
+
+name_enum!(IntendedStdLevel, "/api/v1/name/intendedstdlevelname/", {
+    InternetStandard    => "std",
+    DraftStandard       => "ds",
+    ProposedStandard    => "ps",
+    Informational       => "inf",
+    Experimental        => "exp",
+    BestCurrentPractice => "bcp",
+    Historic            => "hist",
+});
+
+
+name_enum!(DocTag, "/api/v1/name/doctagname/", {
+    AdFollowup               => "ad-f-up",
+    ApprovedInMinutes        => "app-min",
+    AuthorOrEditorNeeded     => "need-aut",
+    AwaitingExpertReview     => "w-expert",
+    AwaitingExternalReview   => "w-extern",
+    AwaitingMerge            => "w-merge",
+    AwaitingReviews          => "w-review",
+    EditorNeeded             => "need-ed",
+    Errata                   => "errata",
+    ExternalParty            => "extpty",
+    IanaCoordination         => "iana-crd",
+    IesgReviewCompleted      => "iesg-com",
+    Other                    => "other",
+    PointRaised              => "point",
+    RevisedIdNeeded          => "need-rev",
+    RevisedIdNeededByAd      => "rev-ad",
+    RevisedIdNeededByIesg    => "rev-iesg",
+    RevisedIdNeededByWg      => "rev-wg",
+    RevisedIdNeededByWglc    => "rev-wglc",
+    ShepherdNeeded           => "need-sh",
+    ShepherdUpdate           => "sheph-u",
+    VerifiedErrata           => "verified-errata",
+    ViaRfcEditor             => "via-rfc",
+    WaitingForDependency     => "w-dep",
+    WaitingForReferencedDoc  => "w-refdoc",
+    WaitingForReferencingDoc => "w-refing",
+});
+
+name_enum!(SubmissionState, "/api/v1/name/draftsubmissionstatename/", {
+    Uploaded                => "uploaded",
+    Validating              => "validating",
+    AwaitingAuthentication  => "auth",
+    AwaitingAuthorApproval  => "aut-appr",
+    AwaitingGroupApproval   => "grp-appr",
+    AwaitingAdApproval      => "ad-appr",
+    AwaitingManualPost      => "manual",
+    WaitingForDraft         => "waiting-for-draft",
+    Cancelled               => "cancel",
+    Posted                  => "posted",
+});
+
+// --------------------------------------------------------------------------------------------------------------------------------
+// Positions that an Area Director can take on a ballot:
+
+name_enum!(BallotPosition, "/api/v1/name/ballotpositionname/", {
+    Yes         => "yes",
+    NoObjection => "noobj",
+    Discuss     => "discuss",
+    Block       => "block",
+    Abstain     => "abstain",
+    Recuse      => "recuse",
+    NoRecord    => "norecord",
+});
+
+// --------------------------------------------------------------------------------------------------------------------------------
+// Names used by groups:
+
+name_enum!(RoleName, "/api/v1/name/rolename/", {
+    Chair                  => "chair",
+    AreaDirector           => "ad",
+    PreAreaDirector        => "pre-ad",
+    Secretary              => "secr",
+    TechnicalAdvisor       => "techadv",
+    Editor                 => "editor",
+    Delegate               => "delegate",
+    Advisor                => "advisor",
+    Lead                   => "lead",
+    Member                 => "member",
+    Reviewer               => "reviewer",
+    Liaison                => "liaison",
+    LiaisonManager         => "liaiman",
+    AuthorizedIndividual   => "auth",
+    AtLarge                => "atlarge",
+    MaterialsManager       => "matman",
+    RecordingManager       => "recman",
+    ExecutiveDirector      => "execdir",
+    AdministrativeDirector => "admdir",
+});
+
+
+name_enum!(GroupMilestoneState, "/api/v1/name/groupmilestonestatename/", {
+    Active          => "active",
+    Deleted         => "deleted",
+    AwaitingReview  => "review",
+    ProposedCharter => "charter",
+});
+
+// --------------------------------------------------------------------------------------------------------------------------------
+// Types of relationship between documents:
+
+name_enum!(Relationship, "/api/v1/name/docrelationshipname/", {
+    Replaces                => "replaces",
+    PossiblyReplaces        => "possibly-replaces",
+    Obsoletes               => "obs",
+    Updates                 => "updates",
+    NormativeReference      => "refnorm",
+    InformativeReference    => "refinfo",
+    UnknownReference        => "refunk",
+    OldReference            => "refold",
+    DownrefApproval         => "downref-approval",
+    ConflictReview          => "conflrev",
+    MovesToBcp              => "tobcp",
+    MovesToExperimental     => "toexp",
+    MovesToHistoric         => "tohist",
+    MovesToInformational    => "toinf",
+    MovesToInternetStandard => "tois",
+    MovesToDraftStandard    => "tods",
+    MovesToProposedStandard => "tops",
+});
+
+
+impl Relationship {
+    // The relationships that are references from one document to another
+    pub fn references() -> Vec<Relationship> {
+        vec![Relationship::NormativeReference, Relationship::InformativeReference,
+             Relationship::UnknownReference,   Relationship::OldReference]
+    }
+}
+
+
+// An entry in the table of relationship types
+#[derive(Deserialize, Debug)]
+pub struct DocRelationshipName {
+    pub resource_uri : Relationship,
+    pub slug         : String,
+    pub name         : String,
+    pub desc         : String,
+    pub used         : bool,
+    pub order        : u64,
+    pub revname      : String
+}
+
+// --------------------------------------------------------------------------------------------------------------------------------
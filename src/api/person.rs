@@ -33,7 +33,7 @@ use super::*;
 // --------------------------------------------------------------------------------------------------------------------------------
 // Types relating to people:
 
-#[derive(Deserialize, Debug, Eq, PartialEq)]
+#[derive(Deserialize, Debug, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
 pub struct PersonUri(pub String);
 
 
@@ -55,7 +55,7 @@ pub struct Person {
 }
 
 
-#[derive(Deserialize, Debug, Eq, PartialEq)]
+#[derive(Deserialize, Debug, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
 pub struct HistoricalPersonUri(pub String);
 
 
@@ -85,7 +85,7 @@ pub struct HistoricalPerson {
 }
 
 
-#[derive(Deserialize, Debug, Eq, PartialEq)]
+#[derive(Deserialize, Debug, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
 pub struct PersonAliasUri(pub String);
 
 
@@ -98,42 +98,3 @@ pub struct PersonAlias {
 }
 
 // --------------------------------------------------------------------------------------------------------------------------------
-
-
-pub struct PersonFilter {
-    query_url   : String,
-    params      : Vec<String>
-}
-
-impl PersonFilter {
-    fn new(query_url : String) -> PersonFilter {
-        PersonFilter {
-            query_url : query_url,
-            params    : Vec::new()
-        }
-    }
-
-    fn since(mut self, date : DateTime<Utc>) -> PersonFilter {
-        unimplemented!();
-    }
-
-    fn until(mut self, date : DateTime<Utc>) -> PersonFilter {
-        unimplemented!();
-    }
-
-    fn with_name(mut self, name : String) -> PersonFilter {
-        self.params.push(format!("name={}", name));
-        self
-    }
-
-    fn with_name_containing(mut self, name : String) -> PersonFilter {
-        self.params.push(format!("name__contains={}", name));
-        self
-    }
-
-    fn fetch<'a>(self) -> DTResult<PaginatedList<'a, Person>> {
-        unimplemented!();
-    }
-}
-
-// --------------------------------------------------------------------------------------------------------------------------------
@@ -0,0 +1,220 @@
+// Copyright (C) 2019 University of Glasgow
+// 
+// Redistribution and use in source and binary forms, with or without
+// modification, are permitted provided that the following conditions 
+// are met:
+// 
+// 1. Redistributions of source code must retain the above copyright notice,
+//    this list of conditions and the following disclaimer.
+// 
+// 2. Redistributions in binary form must reproduce the above copyright
+//    notice, this list of conditions and the following disclaimer in the
+//    documentation and/or other materials provided with the distribution.
+// 
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+// AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+// IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE
+// ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE
+// LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR
+// CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF
+// SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS
+// INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN
+// CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE)
+// ARISING IN ANY WAY OUT OF THE USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE
+// POSSIBILITY OF SUCH DAMAGE.
+//
+// SPDX-License-Identifier: BSD-2-Clause
+
+
+use chrono::prelude::*;
+use serde::Deserialize;
+
+use super::{deserialize_time, deserialize_optional_date};
+use super::document::DocumentUri;
+use super::group::GroupUri;
+use super::name::SubmissionState;
+use super::person::PersonUri;
+
+// --------------------------------------------------------------------------------------------------------------------------------
+// Types relating to draft submissions:
+
+#[derive(Deserialize, Debug, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct SubmissionUri(pub String);
+
+
+// The upload of a revision of a draft
+#[derive(Deserialize, Debug)]
+pub struct Submission {
+    pub id               : u64,
+    pub resource_uri     : SubmissionUri,
+    pub name             : String,
+    pub rev              : String,
+    pub title            : String,
+    #[serde(rename = "abstract")]
+    pub doc_abstract     : String,
+    pub draft            : Option<DocumentUri>,    // None if the submission was never posted
+    pub group            : Option<GroupUri>,
+    pub state            : SubmissionState,
+    pub authors          : String,          // See parse_authors()
+    pub submitter        : String,          // e.g., "Colin Perkins <csp@csperkins.org>"
+    pub replaces         : String,          // Comma separated list of draft names
+    pub file_types       : String,          // e.g., ".txt,.xml"
+    pub file_size        : Option<u64>,
+    pub pages            : Option<u64>,
+    pub words            : Option<u64>,
+    pub checks           : Vec<SubmissionCheckUri>,
+    pub note             : String,
+    pub remote_ip        : String,
+    #[serde(default, deserialize_with="deserialize_optional_date")]
+    pub document_date    : Option<NaiveDate>,
+    #[serde(default, deserialize_with="deserialize_optional_date")]
+    pub submission_date  : Option<NaiveDate>
+}
+
+
+// An author of a submission, as listed in the submitted draft
+#[derive(Deserialize, Debug, Eq, PartialEq)]
+pub struct SubmissionAuthor {
+    pub name        : String,
+    pub email       : Option<String>,
+    #[serde(default)]
+    pub affiliation : Option<String>,
+    #[serde(default)]
+    pub country     : Option<String>
+}
+
+
+impl Submission {
+    // The authors field is a list of authors, but older submissions store
+    // it in Python syntax rather than JSON. This parses either form.
+    pub fn parse_authors(&self) -> Result<Vec<SubmissionAuthor>, serde_json::Error> {
+        match serde_json::from_str(&self.authors) {
+            Ok(authors) => Ok(authors),
+            Err(e) => {
+                let json = python_to_json(&self.authors);
+                serde_json::from_str(&json).map_err(|_| e)
+            }
+        }
+    }
+
+    // The names of the drafts that this submission replaces
+    pub fn replaced_drafts(&self) -> Vec<&str> {
+        self.replaces.split(',').map(|name| name.trim()).filter(|name| !name.is_empty()).collect()
+    }
+
+    // The types of file submitted, e.g., [".txt", ".xml"]
+    pub fn file_types(&self) -> Vec<&str> {
+        self.file_types.split(',').map(|t| t.trim()).filter(|t| !t.is_empty()).collect()
+    }
+}
+
+
+// Convert a Python literal, such as [{u'name': u'J\xf6rg Ott', u'email': None}],
+// to JSON. Strings may be quoted with either ' or ", and may have a u prefix.
+// The \xNN, \uNNNN, and \UNNNNNNNN escapes are converted to JSON \u escapes.
+fn python_to_json(python : &str) -> String {
+    let mut json  = String::with_capacity(python.len());
+    let mut chars = python.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '\'' || c == '"' {
+            json.push('"');
+            while let Some(s) = chars.next() {
+                match s {
+                    '\\' => {
+                        match chars.next() {
+                            Some('\'') => json.push('\''),
+                            Some('"')  => json.push_str("\\\""),
+                            Some(e @ ('\\' | 'b' | 'f' | 'n' | 'r' | 't')) => { json.push('\\'); json.push(e); }
+                            Some(e @ ('x' | 'u' | 'U')) => {
+                                let len = match e { 'x' => 2, 'u' => 4, _ => 8 };
+                                let hex = chars.by_ref().take(len).collect::<String>();
+                                match u32::from_str_radix(&hex, 16) {
+                                    Ok(c) if hex.len() == len && hex.chars().all(|h| h.is_ascii_hexdigit()) && c <= 0x10FFFF => {
+                                        push_json_escape(&mut json, c);
+                                    }
+                                    _ => { json.push_str("\\\\"); json.push(e); json.push_str(&hex); }
+                                }
+                            }
+                            // Python keeps the backslash of an unknown escape
+                            Some(e)    => { json.push_str("\\\\"); json.push(e); }
+                            None       => {}
+                        }
+                    }
+                    _ if s == c => break,
+                    '"'         => json.push_str("\\\""),
+                    _           => json.push(s)
+                }
+            }
+            json.push('"');
+        } else if c.is_alphabetic() {
+            let mut word = c.to_string();
+            while let Some(&w) = chars.peek().filter(|w| w.is_alphanumeric()) {
+                word.push(w);
+                chars.next();
+            }
+            match word.as_str() {
+                "u" if matches!(chars.peek(), Some('\'') | Some('"')) => {}
+                "None"  => json.push_str("null"),
+                "True"  => json.push_str("true"),
+                "False" => json.push_str("false"),
+                _       => json.push_str(&word)
+            }
+        } else {
+            json.push(c);
+        }
+    }
+    json
+}
+
+
+// Append a code point to a JSON string as a \u escape, using a surrogate pair
+// for code points outside the Basic Multilingual Plane.
+fn push_json_escape(json : &mut String, c : u32) {
+    if c < 0x10000 {
+        json.push_str(&format!("\\u{:04x}", c));
+    } else {
+        let c = c - 0x10000;
+        json.push_str(&format!("\\u{:04x}\\u{:04x}", 0xD800 + (c >> 10), 0xDC00 + (c & 0x3FF)));
+    }
+}
+
+
+#[derive(Deserialize, Debug, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct SubmissionCheckUri(pub String);
+
+
+// The result of an automated check run on a submission, e.g., idnits
+#[derive(Deserialize, Debug)]
+pub struct SubmissionCheck {
+    pub id           : u64,
+    pub resource_uri : SubmissionCheckUri,
+    pub submission   : SubmissionUri,
+    #[serde(deserialize_with="deserialize_time")]
+    pub time         : DateTime<Utc>,
+    pub checker      : String,
+    pub passed       : Option<bool>,
+    pub message      : String,
+    pub errors       : Option<u64>,
+    pub warnings     : Option<u64>,
+    pub items        : String,
+    pub symbol       : String
+}
+
+
+#[derive(Deserialize, Debug, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct SubmissionEventUri(pub String);
+
+
+// An event in the processing of a submission, e.g., "Uploaded submission"
+#[derive(Deserialize, Debug)]
+pub struct SubmissionEvent {
+    pub id           : u64,
+    pub resource_uri : SubmissionEventUri,
+    pub submission   : SubmissionUri,
+    #[serde(deserialize_with="deserialize_time")]
+    pub time         : DateTime<Utc>,
+    pub by           : Option<PersonUri>,
+    pub desc         : String
+}
+
+// --------------------------------------------------------------------------------------------------------------------------------
@@ -0,0 +1,808 @@
+// Copyright (C) 2020 University of Glasgow
+//
+// Redistribution and use in source and binary forms, with or without
+// modification, are permitted provided that the following conditions
+// are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice,
+//    this list of conditions and the following disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright
+//    notice, this list of conditions and the following disclaimer in the
+//    documentation and/or other materials provided with the distribution.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+// AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+// IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE
+// ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE
+// LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR
+// CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF
+// SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS
+// INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN
+// CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE)
+// ARISING IN ANY WAY OUT OF THE USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE
+// POSSIBILITY OF SUCH DAMAGE.
+//
+// SPDX-License-Identifier: BSD-2-Clause
+
+// An asynchronous interface to the Datatracker. This provides the same
+// endpoint methods as the blocking Datatracker, and returns the same types,
+// but the methods are async and the paginated lists are Streams.
+
+use std::collections::{HashSet, VecDeque};
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use chrono::prelude::*;
+use futures::{Stream, TryStreamExt};
+use serde::Deserialize;
+
+use crate::*;
+use crate::cache::CacheCheck;
+
+// =================================================================================================================================
+// Asynchronous version of PaginatedList:
+
+type PageFuture<'a, T> = Pin<Box<dyn Future<Output = DTResult<Page<T>>> + Send + 'a>>;
+
+pub struct AsyncPaginatedList<'a, T> {
+    iter        : <Vec<T> as IntoIterator>::IntoIter,
+    next        : Option<String>,
+    fetch       : Option<PageFuture<'a, T>>,
+    dt          : &'a AsyncDatatracker,
+    total_count : u32,
+    limit       : u32,
+    offset      : u32
+}
+
+impl<'a, T> AsyncPaginatedList<'a, T>
+    where for<'de> T: Deserialize<'de> + Send + 'a
+{
+    pub async fn new(dt: &'a AsyncDatatracker, url : String) -> DTResult<AsyncPaginatedList<'a, T>> {
+        let pl : Page<T> = dt.retrieve(&url).await?;
+
+        Ok(Self {
+            iter        : pl.objects.into_iter(),
+            next        : pl.meta.next,
+            fetch       : None,
+            total_count : pl.meta.total_count,
+            limit       : pl.meta.limit,
+            offset      : pl.meta.offset,
+            dt
+        })
+    }
+
+    // As PaginatedList::empty()
+    pub fn empty(dt: &'a AsyncDatatracker) -> AsyncPaginatedList<'a, T> {
+        Self {
+            iter        : Vec::new().into_iter(),
+            next        : None,
+            fetch       : None,
+            total_count : 0,
+            limit       : 0,
+            offset      : 0,
+            dt
+        }
+    }
+}
+
+impl<'a, T> AsyncPaginatedList<'a, T> {
+    // As PaginatedList::total_count()
+    pub fn total_count(&self) -> u32 {
+        self.total_count
+    }
+
+    // As PaginatedList::limit()
+    pub fn limit(&self) -> u32 {
+        self.limit
+    }
+
+    // As PaginatedList::offset()
+    pub fn offset(&self) -> u32 {
+        self.offset
+    }
+}
+
+// The only pinned state is the boxed page future, which is pinned on the heap
+impl<'a, T> Unpin for AsyncPaginatedList<'a, T> {}
+
+impl<'a, T> Stream for AsyncPaginatedList<'a, T>
+    where for<'de> T: Deserialize<'de> + Send + 'a
+{
+    type Item = DTResult<T>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            if let Some(x) = self.iter.next() {
+                self.offset += 1;
+                return Poll::Ready(Some(Ok(x)));
+            }
+            match self.fetch {
+                Some(ref mut fetch) => {
+                    match fetch.as_mut().poll(cx) {
+                        Poll::Pending => {
+                            return Poll::Pending;
+                        }
+                        Poll::Ready(Ok(pl)) => {
+                            self.fetch       = None;
+                            self.next        = pl.meta.next;
+                            self.total_count = pl.meta.total_count;
+                            self.limit       = pl.meta.limit;
+                            self.offset      = pl.meta.offset;
+                            self.iter        = pl.objects.into_iter();
+                        }
+                        Poll::Ready(Err(e)) => {
+                            // Keep the URL of the page, so the next poll retries it, as the blocking PaginatedList does
+                            self.fetch = None;
+                            return Poll::Ready(Some(Err(e)));
+                        }
+                    }
+                }
+                None => {
+                    match self.next.clone() {
+                        Some(url) => {
+                            let dt = self.dt;
+                            self.fetch = Some(Box::pin(async move { dt.retrieve::<Page<T>>(&url).await }));
+                        }
+                        None => {
+                            return Poll::Ready(None);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = remaining(self.iter.len(), self.next.is_some(), self.total_count, self.offset);
+        (remaining, None)
+    }
+}
+
+// =================================================================================================================================
+// Asynchronous IETF Datatracker API:
+
+pub struct AsyncDatatracker {
+    pub(crate) connection   : reqwest::Client,
+    pub(crate) base_url     : String,
+    pub(crate) fixtures     : Option<Fixtures>,
+    pub(crate) cache        : Option<Cache>,
+    pub(crate) retry        : RetryPolicy,
+    pub(crate) rate_limiter : Option<RateLimiter>
+}
+
+
+impl AsyncDatatracker {
+    async fn retrieve<T>(&self, path : &str) -> DTResult<T>
+        where for<'de> T: Deserialize<'de>
+    {
+        if !path.starts_with("/api/") {
+            return Err(DatatrackerError::InvalidUri(path.to_string()));
+        }
+
+        let (status, body) = match self.fixtures {
+            Some(ref fixtures) if fixtures.mode() == FixtureMode::Replay => {
+                let fixture = fixtures.load_async(path).await?;
+                (fixture.status, fixture.body)
+            }
+            _ => {
+                let (status, body) = self.fetch(path).await?;
+                if let Some(ref fixtures) = self.fixtures {
+                    fixtures.save_async(path, status, &body).await?;
+                }
+                (status, body)
+            }
+        };
+        decode_response(&format!("{}{}", self.base_url, path), status, &body)
+    }
+
+
+    // Fetch a URL path from the server, or from the cache if enabled
+    async fn fetch(&self, path : &str) -> DTResult<(u16, String)> {
+        let url   = format!("{}{}", self.base_url, path);
+        let stale = match self.cache {
+            Some(ref cache) => {
+                match cache.check_async(&url).await? {
+                    CacheCheck::Hit(body)         => return Ok((200, body)),
+                    CacheCheck::Revalidate(entry) => Some(entry),
+                    CacheCheck::Miss              => None
+                }
+            }
+            None => None
+        };
+
+        let mut attempt = 0;
+        let res = loop {
+            if let Some(ref limiter) = self.rate_limiter {
+                tokio::time::sleep(limiter.reserve()).await;
+            }
+            let mut req = self.connection.get(&url);
+            if let Some(ref entry) = stale {
+                req = req.headers(Cache::revalidation_headers(entry));
+            }
+            match req.send().await {
+                Ok(res) if self.retry.should_retry(attempt) && RetryPolicy::is_retryable_status(res.status()) => {
+                    match self.retry.delay(attempt, retry_after(res.headers())) {
+                        Some(delay) => tokio::time::sleep(delay).await,
+                        None        => return Err(DatatrackerError::RateLimited { url, retry_after : retry_after(res.headers()) })
+                    }
+                }
+                Ok(res) => {
+                    break res;
+                }
+                Err(e) if self.retry.should_retry(attempt) && RetryPolicy::is_retryable_error(&e) => {
+                    tokio::time::sleep(self.retry.backoff(attempt)).await;
+                }
+                Err(e) => {
+                    return Err(e.into());
+                }
+            }
+            attempt += 1;
+        };
+
+        if res.status() == reqwest::StatusCode::TOO_MANY_REQUESTS {
+            return Err(DatatrackerError::RateLimited { url, retry_after : retry_after(res.headers()) });
+        }
+        let status  = res.status().as_u16();
+        let headers = res.headers().clone();
+        let body    = res.text().await?;
+
+        match self.cache {
+            Some(ref cache) => cache.update_async(&url, stale, status, &headers, body).await,
+            None            => Ok((status, body))
+        }
+    }
+
+
+    // Connect to the main IETF Datatracker, with default settings. Use
+    // Datatracker::builder() and build_async() to connect to a different instance.
+    pub fn new() -> Self {
+        DatatrackerBuilder::new().build_async().expect("Cannot initialise Datatracker connection")
+    }
+
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+
+    // Fetch the resource identified by a URI, as Datatracker::get()
+    pub async fn get<U: ResourceUri>(&self, uri : &U) -> DTResult<U::Resource> {
+        self.retrieve::<U::Resource>(uri.as_str()).await
+    }
+
+
+    // Start a query on the list endpoint for type T, for example:
+    //   dt.query::<Person>().contains("name", "Perkins").fetch().await
+    pub fn query<T: Endpoint>(&self) -> Query<'_, AsyncDatatracker, T> {
+        Query::new(self)
+    }
+
+
+    // ----------------------------------------------------------------------------------------------------------------------------
+    // Datatracker API endpoints returning information about email addresses:
+
+    pub async fn email(&self, email_uri: &EmailUri) -> DTResult<Email> {
+        self.get(email_uri).await
+    }
+
+    pub async fn email_from_address(&self, email_addr : &str) -> DTResult<Email> {
+        let url = format!("/api/v1/person/email/{}/", email_addr);
+        self.retrieve::<Email>(&url).await
+    }
+
+
+    pub async fn email_history_for_address<'a>(&'a self, email_addr : &str) -> DTResult<AsyncPaginatedList<'a, HistoricalEmail>> {
+        self.query::<HistoricalEmail>().exact("address", email_addr).fetch().await
+    }
+
+
+    pub async fn email_history_for_person<'a>(&'a self, person : &Person) -> DTResult<AsyncPaginatedList<'a, HistoricalEmail>> {
+        self.query::<HistoricalEmail>().exact("person", person.id).fetch().await
+    }
+
+
+    // ----------------------------------------------------------------------------------------------------------------------------
+    // Datatracker API endpoints returning information about people:
+
+    pub async fn person(&self, person_uri : &PersonUri) -> DTResult<Person> {
+        self.get(person_uri).await
+    }
+
+
+    pub async fn person_from_email(&self, email : &EmailUri) -> DTResult<Person> {
+        let person = self.email(email).await?.person;
+        self.person(&person).await
+    }
+
+    pub async fn person_from_email_address(&self, email_addr : &str) -> DTResult<Person> {
+        let person = self.email_from_address(email_addr).await?.person;
+        self.person(&person).await
+    }
+
+
+    pub async fn person_aliases<'a>(&'a self, person : &Person) -> DTResult<AsyncPaginatedList<'a, PersonAlias>> {
+        self.query::<PersonAlias>().exact("person", person.id).fetch().await
+    }
+
+
+    pub async fn person_history<'a>(&'a self, person : &Person) -> DTResult<AsyncPaginatedList<'a, HistoricalPerson>> {
+        self.query::<HistoricalPerson>().exact("id", person.id).fetch().await
+    }
+
+
+    pub async fn document_authors<'a>(&'a self, doc : &Document) -> DTResult<AsyncPaginatedList<'a, DocumentAuthor>> {
+        self.query::<DocumentAuthor>().document(doc).order_by("order").fetch().await
+    }
+
+
+    pub async fn documents_authored_by<'a>(&'a self, person : &Person) -> DTResult<AsyncPaginatedList<'a, DocumentAuthor>> {
+        self.query::<DocumentAuthor>().person(&person.resource_uri).fetch().await
+    }
+
+
+    pub async fn people<'a>(&'a self) -> DTResult<AsyncPaginatedList<'a, Person>> {
+        self.query::<Person>().fetch().await
+    }
+
+
+    pub async fn people_with_name<'a>(&'a self, name: &str) -> DTResult<AsyncPaginatedList<'a, Person>> {
+        self.query::<Person>().exact("name", name).fetch().await
+    }
+
+
+    pub async fn people_with_name_containing<'a>(&'a self, name_contains: &str) -> DTResult<AsyncPaginatedList<'a, Person>> {
+        self.query::<Person>().contains("name", name_contains).fetch().await
+    }
+
+
+    // ----------------------------------------------------------------------------------------------------------------------------
+    // Datatracker API endpoints returning information about documents:
+
+    pub async fn document(&self, doc_uri : &DocumentUri) -> DTResult<Document> {
+        self.get(doc_uri).await
+    }
+
+
+    pub async fn document_from_draft(&self, name : &str) -> DTResult<Document> {
+        self.get(&DocumentUri::from_name(name)).await
+    }
+
+
+    pub async fn document_from_rfc(&self, rfc : u64) -> DTResult<Document> {
+        self.document_from_alias(&format!("rfc{}", rfc)).await
+    }
+
+
+    pub async fn document_from_alias(&self, name : &str) -> DTResult<Document> {
+        match self.doc_aliases(name).await?.try_next().await? {
+            Some(alias) => self.document(&alias.document).await,
+            None        => Err(DatatrackerError::NotFound)
+        }
+    }
+
+
+    pub async fn documents_from_alias(&self, name : &str) -> DTResult<Vec<Document>> {
+        let aliases = self.doc_aliases(name).await?.try_collect::<Vec<_>>().await?;
+        let mut docs = Vec::new();
+        for alias in aliases {
+            docs.push(self.document(&alias.document).await?);
+        }
+        Ok(docs)
+    }
+
+
+    pub async fn rfc_number(&self, doc : &Document) -> DTResult<Option<u64>> {
+        let mut aliases = self.doc_aliases_for_document(doc).await?;
+        while let Some(alias) = aliases.try_next().await? {
+            if let Some(rfc) = alias.name.strip_prefix("rfc").and_then(|n| n.parse().ok()) {
+                return Ok(Some(rfc));
+            }
+        }
+        Ok(None)
+    }
+
+
+    pub async fn doc_alias(&self, alias_uri : &DocAliasUri) -> DTResult<DocAlias> {
+        self.get(alias_uri).await
+    }
+
+
+    pub async fn doc_aliases<'a>(&'a self, name : &str) -> DTResult<AsyncPaginatedList<'a, DocAlias>> {
+        self.query::<DocAlias>().exact("name", name).fetch().await
+    }
+
+
+    pub async fn doc_aliases_for_document<'a>(&'a self, doc : &Document) -> DTResult<AsyncPaginatedList<'a, DocAlias>> {
+        self.query::<DocAlias>().exact("document", doc.id).fetch().await
+    }
+
+
+    pub fn documents(&self) -> Query<'_, AsyncDatatracker, Document> {
+        self.query::<Document>()
+    }
+
+
+    pub async fn document_for_alias(&self, alias_uri : &DocAliasUri) -> DTResult<Document> {
+        let alias = self.doc_alias(alias_uri).await?;
+        self.document(&alias.document).await
+    }
+
+
+    pub async fn related_documents_from<'a>(&'a self, doc : &Document) -> DTResult<AsyncPaginatedList<'a, RelatedDocument>> {
+        self.query::<RelatedDocument>().source(doc).fetch().await
+    }
+
+
+    pub async fn related_documents_to<'a>(&'a self, doc : &Document) -> DTResult<AsyncPaginatedList<'a, RelatedDocument>> {
+        let aliases = self.doc_alias_ids(doc).await?;
+        if aliases.is_empty() {
+            return Ok(AsyncPaginatedList::empty(self));
+        }
+        self.query::<RelatedDocument>().is_in("target", &aliases).fetch().await
+    }
+
+
+    async fn doc_alias_ids(&self, doc : &Document) -> DTResult<Vec<u64>> {
+        self.doc_aliases_for_document(doc).await?.map_ok(|a| a.id).try_collect().await
+    }
+
+
+    pub async fn references<'a>(&'a self, doc : &Document) -> DTResult<AsyncPaginatedList<'a, RelatedDocument>> {
+        self.query::<RelatedDocument>().source(doc).relationships(&Relationship::references()).fetch().await
+    }
+
+
+    pub async fn referenced_by<'a>(&'a self, doc : &Document) -> DTResult<AsyncPaginatedList<'a, RelatedDocument>> {
+        let aliases = self.doc_alias_ids(doc).await?;
+        if aliases.is_empty() {
+            return Ok(AsyncPaginatedList::empty(self));
+        }
+        self.query::<RelatedDocument>().is_in("target", &aliases).relationships(&Relationship::references()).fetch().await
+    }
+
+
+    pub async fn related_closure(&self, doc : &Document, relationships : &[Relationship], direction : RelationshipDirection) -> DTResult<Vec<Document>> {
+        let mut seen   = HashSet::new();
+        let mut queue  = VecDeque::from(self.related_uris(doc, relationships, direction).await?);
+        let mut result = Vec::new();
+        seen.insert(doc.resource_uri.clone());
+        while let Some(uri) = queue.pop_front() {
+            if seen.insert(uri.clone()) {
+                let related = self.document(&uri).await?;
+                queue.extend(self.related_uris(&related, relationships, direction).await?);
+                result.push(related);
+            }
+        }
+        Ok(result)
+    }
+
+    async fn related_uris(&self, doc : &Document, relationships : &[Relationship], direction : RelationshipDirection) -> DTResult<Vec<DocumentUri>> {
+        match direction {
+            RelationshipDirection::Outgoing => {
+                let rels = self.query::<RelatedDocument>()
+                               .source(doc)
+                               .relationships(relationships)
+                               .fetch().await?
+                               .try_collect::<Vec<_>>().await?;
+                let mut uris = Vec::new();
+                for rel in rels {
+                    uris.push(self.doc_alias(&rel.target).await?.document);
+                }
+                Ok(uris)
+            }
+            RelationshipDirection::Incoming => {
+                let aliases = self.doc_alias_ids(doc).await?;
+                if aliases.is_empty() {
+                    return Ok(Vec::new());
+                }
+                self.query::<RelatedDocument>()
+                    .is_in("target", &aliases)
+                    .relationships(relationships)
+                    .fetch().await?
+                    .map_ok(|r| r.source)
+                    .try_collect().await
+            }
+        }
+    }
+
+
+    pub async fn replaces_chain(&self, doc : &Document) -> DTResult<Vec<Document>> {
+        self.related_closure(doc, &[Relationship::Replaces], RelationshipDirection::Outgoing).await
+    }
+
+
+    pub async fn doc_relationship_names<'a>(&'a self) -> DTResult<AsyncPaginatedList<'a, DocRelationshipName>> {
+        self.query::<DocRelationshipName>().fetch().await
+    }
+
+
+    pub async fn doc_event(&self, event_uri : &DocEventUri) -> DTResult<BaseDocEvent> {
+        self.get(event_uri).await
+    }
+
+
+    pub fn doc_events(&self) -> Query<'_, AsyncDatatracker, BaseDocEvent> {
+        self.query::<BaseDocEvent>()
+    }
+
+
+    pub async fn doc_events_for_document(&self, doc : &Document) -> DTResult<Vec<DocEvent>> {
+        self.doc_events().doc(doc).order_by("time").fetch_typed().await
+    }
+
+
+    pub async fn deleted_event(&self, event_uri : &DeletedEventUri) -> DTResult<DeletedEvent> {
+        self.get(event_uri).await
+    }
+
+
+    pub fn deleted_events(&self) -> Query<'_, AsyncDatatracker, DeletedEvent> {
+        self.query::<DeletedEvent>()
+    }
+
+
+    pub async fn doc_state(&self, state_uri: &DocStateUri) -> DTResult<DocState> {
+        self.get(state_uri).await
+    }
+
+
+    pub async fn doc_states<'a>(&'a self) -> DTResult<AsyncPaginatedList<'a, DocState>> {
+        self.query::<DocState>().fetch().await
+    }
+
+
+    pub async fn doc_state_type(&self, state_type_uri: &DocStateTypeUri) -> DTResult<DocStateType> {
+        self.get(state_type_uri).await
+    }
+
+
+    pub async fn doc_state_types<'a>(&'a self) -> DTResult<AsyncPaginatedList<'a, DocStateType>> {
+        self.query::<DocStateType>().fetch().await
+    }
+
+
+    pub async fn ballot_type(&self, ballot_type_uri : &BallotTypeUri) -> DTResult<BallotType> {
+        self.get(ballot_type_uri).await
+    }
+
+
+    pub async fn ballot_types<'a>(&'a self) -> DTResult<AsyncPaginatedList<'a, BallotType>> {
+        self.query::<BallotType>().fetch().await
+    }
+
+
+    pub async fn ballot_position_names<'a>(&'a self) -> DTResult<AsyncPaginatedList<'a, BallotPositionName>> {
+        self.query::<BallotPositionName>().fetch().await
+    }
+
+
+    pub async fn current_ballot(&self, doc : &Document) -> DTResult<Option<Ballot>> {
+        let events = self.query::<BallotDocEvent>().doc(doc).order_by("-time").fetch().await?.try_collect::<Vec<_>>().await?;
+        let (ballot, closed) = match latest_ballot(events) {
+            Some(latest) => latest,
+            None         => return Ok(None)
+        };
+        let positions = self.query::<BallotPositionDocEvent>().ballot(&ballot).order_by("time").fetch().await?.try_collect::<Vec<_>>().await?;
+        Ok(Some(Ballot::new(ballot, closed, positions)))
+    }
+
+
+    pub async fn state_machine(&self) -> DTResult<StateMachine> {
+        let types  = self.doc_state_types().await?.try_collect::<Vec<_>>().await?;
+        let states = self.doc_states().await?.try_collect::<Vec<_>>().await?;
+        Ok(StateMachine::new(types, states))
+    }
+
+
+    // ----------------------------------------------------------------------------------------------------------------------------
+    // Datatracker API endpoints returning information about draft submissions:
+
+    pub async fn submission(&self, submission_uri : &SubmissionUri) -> DTResult<Submission> {
+        self.get(submission_uri).await
+    }
+
+
+    pub fn submissions(&self) -> Query<'_, AsyncDatatracker, Submission> {
+        self.query::<Submission>()
+    }
+
+
+    pub async fn submissions_for_document<'a>(&'a self, doc : &Document) -> DTResult<AsyncPaginatedList<'a, Submission>> {
+        self.submissions().document(doc).order_by("submission_date").fetch().await
+    }
+
+
+    pub async fn submission_checks<'a>(&'a self, submission : &Submission) -> DTResult<AsyncPaginatedList<'a, SubmissionCheck>> {
+        self.query::<SubmissionCheck>().exact("submission", submission.id).fetch().await
+    }
+
+
+    pub async fn submission_events<'a>(&'a self, submission : &Submission) -> DTResult<AsyncPaginatedList<'a, SubmissionEvent>> {
+        self.query::<SubmissionEvent>().exact("submission", submission.id).order_by("time").fetch().await
+    }
+
+
+    // ----------------------------------------------------------------------------------------------------------------------------
+    // Datatracker API endpoints returning information about groups:
+
+    pub async fn group(&self, group_uri : &GroupUri) -> DTResult<Group> {
+        self.get(group_uri).await
+    }
+
+
+    pub async fn group_from_acronym(&self, acronym : &str) -> DTResult<Group> {
+        match self.groups().exact("acronym", acronym).fetch().await?.try_next().await? {
+            Some(group) => Ok(group),
+            None        => Err(DatatrackerError::NotFound)
+        }
+    }
+
+
+    pub fn groups(&self) -> Query<'_, AsyncDatatracker, Group> {
+        self.query::<Group>()
+    }
+
+
+    pub async fn group_tree(&self) -> DTResult<GroupTree> {
+        Ok(GroupTree::new(self.groups().limit(1000).fetch().await?.try_collect::<Vec<_>>().await?))
+    }
+
+
+    pub async fn group_type(&self, group_type_uri : &GroupTypeUri) -> DTResult<GroupType> {
+        self.get(group_type_uri).await
+    }
+
+
+    pub async fn group_types<'a>(&'a self) -> DTResult<AsyncPaginatedList<'a, GroupType>> {
+        self.query::<GroupType>().fetch().await
+    }
+
+
+    pub async fn group_state(&self, group_state_uri : &GroupStateUri) -> DTResult<GroupState> {
+        self.get(group_state_uri).await
+    }
+
+
+    pub async fn group_states<'a>(&'a self) -> DTResult<AsyncPaginatedList<'a, GroupState>> {
+        self.query::<GroupState>().fetch().await
+    }
+
+
+    pub async fn group_history(&self, group_history_uri : &GroupHistoryUri) -> DTResult<GroupHistory> {
+        self.get(group_history_uri).await
+    }
+
+
+    pub async fn group_history_for_group<'a>(&'a self, group : &Group) -> DTResult<AsyncPaginatedList<'a, GroupHistory>> {
+        self.query::<GroupHistory>().group(&group.resource_uri).order_by("time").fetch().await
+    }
+
+
+    pub async fn group_at(&self, group_uri : &GroupUri, time : DateTime<Utc>) -> DTResult<Option<Group>> {
+        let group = self.group(group_uri).await?;
+        if time >= group.time {
+            return Ok(Some(group));
+        }
+        match self.query::<GroupHistory>().group(group_uri).lte("time", time).order_by("-time").limit(1).fetch().await?.try_next().await? {
+            Some(snapshot) => Ok(Some(snapshot.into_group(&group))),
+            None           => Ok(None)
+        }
+    }
+
+
+    pub async fn group_event(&self, event_uri : &GroupEventUri) -> DTResult<BaseGroupEvent> {
+        self.get(event_uri).await
+    }
+
+
+    pub fn group_events(&self) -> Query<'_, AsyncDatatracker, BaseGroupEvent> {
+        self.query::<BaseGroupEvent>()
+    }
+
+
+    pub async fn group_timeline(&self, group : &Group) -> DTResult<Vec<GroupEvent>> {
+        self.group_events().group(&group.resource_uri).order_by("time").fetch_typed().await
+    }
+
+
+    pub async fn role(&self, role_uri : &RoleUri) -> DTResult<Role> {
+        self.get(role_uri).await
+    }
+
+
+    pub fn roles(&self) -> Query<'_, AsyncDatatracker, Role> {
+        self.query::<Role>()
+    }
+
+
+    pub fn role_history(&self) -> Query<'_, AsyncDatatracker, RoleHistory> {
+        self.query::<RoleHistory>()
+    }
+
+
+    pub async fn group_roles<'a>(&'a self, group : &Group, name : &RoleName) -> DTResult<AsyncPaginatedList<'a, Role>> {
+        self.roles().group(&group.resource_uri).name(name).fetch().await
+    }
+
+
+    pub async fn roles_for_person<'a>(&'a self, person : &Person) -> DTResult<AsyncPaginatedList<'a, Role>> {
+        self.roles().person(&person.resource_uri).fetch().await
+    }
+
+
+    pub async fn role_history_for_person<'a>(&'a self, person : &Person) -> DTResult<AsyncPaginatedList<'a, RoleHistory>> {
+        self.role_history().person(&person.resource_uri).fetch().await
+    }
+
+
+    pub async fn group_role_holders_at(&self, group : &Group, name : &RoleName, time : DateTime<Utc>) -> DTResult<Vec<PersonUri>> {
+        let mut people = if time >= group.time {
+            self.group_roles(group, name).await?.map_ok(|r| r.person).try_collect::<Vec<_>>().await?
+        } else {
+            let snapshot = self.query::<GroupHistory>().group(&group.resource_uri).lte("time", time).order_by("-time").limit(1).fetch().await?.try_next().await?;
+            match snapshot {
+                Some(snapshot) => self.role_history().group(&snapshot.resource_uri).name(name).fetch().await?
+                                      .map_ok(|r| r.person)
+                                      .try_collect::<Vec<_>>().await?,
+                None => Vec::new()
+            }
+        };
+        people.sort();
+        people.dedup();
+        Ok(people)
+    }
+
+
+    pub async fn group_milestone(&self, milestone_uri : &GroupMilestoneUri) -> DTResult<GroupMilestone> {
+        self.get(milestone_uri).await
+    }
+
+
+    pub fn group_milestones(&self) -> Query<'_, AsyncDatatracker, GroupMilestone> {
+        self.query::<GroupMilestone>()
+    }
+
+
+    pub async fn milestones_for_group<'a>(&'a self, group : &Group) -> DTResult<AsyncPaginatedList<'a, GroupMilestone>> {
+        self.group_milestones().group(&group.resource_uri).order_by("due").fetch().await
+    }
+
+
+    pub async fn overdue_milestones(&self, group : &Group, date : NaiveDate) -> DTResult<Vec<GroupMilestone>> {
+        self.group_milestones().group(&group.resource_uri).state(&GroupMilestoneState::Active).due_before(date).order_by("due").fetch().await?
+            .try_filter(|milestone| futures::future::ready(milestone.is_overdue(date)))
+            .try_collect().await
+    }
+
+
+    pub async fn milestone_history<'a>(&'a self, milestone : &GroupMilestone) -> DTResult<AsyncPaginatedList<'a, GroupMilestoneHistory>> {
+        self.query::<GroupMilestoneHistory>().exact("milestone", milestone.id).order_by("time").fetch().await
+    }
+
+
+    pub async fn milestone_due_dates(&self, milestone : &GroupMilestone) -> DTResult<Vec<(DateTime<Utc>, NaiveDate)>> {
+        let history = self.milestone_history(milestone).await?.try_collect::<Vec<_>>().await?;
+        Ok(milestone.due_dates(&history))
+    }
+
+
+    pub async fn milestone_events<'a>(&'a self, group : &Group) -> DTResult<AsyncPaginatedList<'a, MilestoneGroupEvent>> {
+        self.query::<MilestoneGroupEvent>().group(&group.resource_uri).order_by("time").fetch().await
+    }
+
+
+    pub async fn group_milestone_state_names<'a>(&'a self) -> DTResult<AsyncPaginatedList<'a, GroupMilestoneStateName>> {
+        self.query::<GroupMilestoneStateName>().fetch().await
+    }
+}
+
+
+impl Default for AsyncDatatracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// =================================================================================================================================
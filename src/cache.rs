@@ -0,0 +1,261 @@
+// Copyright (C) 2020 University of Glasgow
+//
+// Redistribution and use in source and binary forms, with or without
+// modification, are permitted provided that the following conditions
+// are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice,
+//    this list of conditions and the following disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright
+//    notice, this list of conditions and the following disclaimer in the
+//    documentation and/or other materials provided with the distribution.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+// AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+// IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE
+// ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE
+// LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR
+// CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF
+// SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS
+// INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN
+// CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE)
+// ARISING IN ANY WAY OUT OF THE USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE
+// POSSIBILITY OF SUCH DAMAGE.
+//
+// SPDX-License-Identifier: BSD-2-Clause
+
+// A persistent, on-disk, cache of Datatracker responses, keyed by URL. The key
+// includes the scheme and host, so responses from different Datatracker
+// instances sharing a cache directory are kept apart. Cached responses are
+// used without contacting the server until their time-to-live expires, after
+// which they are revalidated using the ETag and Last-Modified headers that
+// the server sent with the original response. In cache-only mode the server
+// is never contacted, and the cached responses are used whatever their age.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use chrono::prelude::*;
+use reqwest::header::{HeaderMap, HeaderValue, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+use serde::{Deserialize, Serialize};
+
+use crate::api::*;
+use crate::fixtures::{escape_filename, url_to_filename};
+
+// =================================================================================================
+
+const DEFAULT_TTL : Duration = Duration::from_secs(24 * 60 * 60);
+
+// Split a URL, such as "https://datatracker.ietf.org/api/v1/doc/state/", into the
+// scheme and host, "https://datatracker.ietf.org", and the path and query.
+fn split_url(url : &str) -> (&str, &str) {
+    let start = url.find("://").map(|i| i + 3).unwrap_or(0);
+    match url[start..].find('/') {
+        Some(i) => url.split_at(start + i),
+        None    => (url, "")
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CacheEntry {
+    pub url           : String,
+    pub etag          : Option<String>,
+    pub last_modified : Option<String>,
+    pub fetched       : i64,    // Unix timestamp
+    pub body          : String
+}
+
+
+// The result of checking the cache before making a request
+pub(crate) enum CacheCheck {
+    Hit(String),
+    Revalidate(CacheEntry),
+    Miss
+}
+
+
+#[derive(Debug)]
+pub struct Cache {
+    dir         : PathBuf,
+    default_ttl : Duration,
+    ttls        : Vec<(String, Duration)>,
+    cache_only  : bool
+}
+
+
+impl Cache {
+    pub fn new(dir : &Path) -> Self {
+        Cache {
+            dir         : dir.to_path_buf(),
+            default_ttl : DEFAULT_TTL,
+            ttls        : Vec::new(),
+            cache_only  : false
+        }
+    }
+
+    // The time-to-live for responses that don't match any of the
+    // prefixes given to ttl(). The default is one day.
+    pub fn default_ttl(mut self, ttl : Duration) -> Self {
+        self.default_ttl = ttl;
+        self
+    }
+
+    // The time-to-live for responses from endpoints starting with the given
+    // path, such as "/api/v1/person/person/" or "/api/v1/doc/state/". This
+    // applies to both individual resources and to paginated lists.
+    pub fn ttl(mut self, path_prefix : &str, ttl : Duration) -> Self {
+        self.ttls.push((path_prefix.to_string(), ttl));
+        self
+    }
+
+    // Never contact the server; use cached responses whatever their age,
+    // and fail with DatatrackerError::NotInCache for anything else.
+    pub fn cache_only(mut self, cache_only : bool) -> Self {
+        self.cache_only = cache_only;
+        self
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn is_cache_only(&self) -> bool {
+        self.cache_only
+    }
+
+    // The time-to-live for a URL path. The longest matching prefix wins.
+    pub fn ttl_for(&self, url : &str) -> Duration {
+        self.ttls.iter()
+                 .filter(|(prefix, _)| url.starts_with(prefix.as_str()))
+                 .max_by_key(|(prefix, _)| prefix.len())
+                 .map(|(_, ttl)| *ttl)
+                 .unwrap_or(self.default_ttl)
+    }
+
+    // The file holding the entry for a URL, such as "https://datatracker.ietf.org/api/v1/doc/state/".
+    // Entries are stored in a subdirectory for each scheme and host.
+    pub fn path_for(&self, url : &str) -> PathBuf {
+        let (origin, path) = split_url(url);
+        self.dir.join(escape_filename(origin)).join(url_to_filename(path))
+    }
+
+    pub fn load(&self, url : &str) -> DTResult<Option<CacheEntry>> {
+        let path = self.path_for(url);
+        Self::decode(path.clone(), fs::read_to_string(&path))
+    }
+
+    // Treat corrupt entries as missing, so they get replaced
+    fn decode(path : PathBuf, data : io::Result<String>) -> DTResult<Option<CacheEntry>> {
+        match data {
+            Ok(data) => Ok(serde_json::from_str(&data).ok()),
+            Err(ref e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(DatatrackerError::CacheError(path, e))
+        }
+    }
+
+    pub(crate) fn check(&self, url : &str) -> DTResult<CacheCheck> {
+        self.classify(url, self.load(url)?)
+    }
+
+    // As check(), but without blocking the async runtime
+    pub(crate) async fn check_async(&self, url : &str) -> DTResult<CacheCheck> {
+        let path  = self.path_for(url);
+        let entry = Self::decode(path.clone(), tokio::fs::read_to_string(&path).await)?;
+        self.classify(url, entry)
+    }
+
+    fn classify(&self, url : &str, entry : Option<CacheEntry>) -> DTResult<CacheCheck> {
+        match entry {
+            Some(entry) => {
+                let age = Utc::now().timestamp() - entry.fetched;
+                if self.cache_only || (age >= 0 && (age as u64) < self.ttl_for(split_url(url).1).as_secs()) {
+                    Ok(CacheCheck::Hit(entry.body))
+                } else {
+                    Ok(CacheCheck::Revalidate(entry))
+                }
+            }
+            None => {
+                if self.cache_only {
+                    Err(DatatrackerError::NotInCache(url.to_string()))
+                } else {
+                    Ok(CacheCheck::Miss)
+                }
+            }
+        }
+    }
+
+    // The headers needed to revalidate a stale entry
+    pub(crate) fn revalidation_headers(entry : &CacheEntry) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        if let Some(etag) = entry.etag.as_ref().and_then(|v| v.parse().ok()) {
+            headers.insert(IF_NONE_MATCH, etag);
+        }
+        if let Some(last_modified) = entry.last_modified.as_ref().and_then(|v| v.parse().ok()) {
+            headers.insert(IF_MODIFIED_SINCE, last_modified);
+        }
+        headers
+    }
+
+    // Update the cache with a response from the server, returning the status
+    // and body to use. A "304 Not Modified" response revalidates the stale
+    // entry, and the cached body is used.
+    pub(crate) fn update(&self, url : &str, stale : Option<CacheEntry>, status : u16, headers : &HeaderMap, body : String) -> DTResult<(u16, String)> {
+        let (entry, result) = Self::updated_entry(url, stale, status, headers, body);
+        if let Some(entry) = entry {
+            let (dir, path, data) = self.encode(&entry)?;
+            fs::create_dir_all(&dir).map_err(|e| DatatrackerError::CacheError(dir, e))?;
+            fs::write(&path, data).map_err(|e| DatatrackerError::CacheError(path, e))?;
+        }
+        Ok(result)
+    }
+
+    // As update(), but without blocking the async runtime
+    pub(crate) async fn update_async(&self, url : &str, stale : Option<CacheEntry>, status : u16, headers : &HeaderMap, body : String) -> DTResult<(u16, String)> {
+        let (entry, result) = Self::updated_entry(url, stale, status, headers, body);
+        if let Some(entry) = entry {
+            let (dir, path, data) = self.encode(&entry)?;
+            tokio::fs::create_dir_all(&dir).await.map_err(|e| DatatrackerError::CacheError(dir, e))?;
+            tokio::fs::write(&path, data).await.map_err(|e| DatatrackerError::CacheError(path, e))?;
+        }
+        Ok(result)
+    }
+
+    // The entry to write to the cache, if any, and the status and body to use
+    fn updated_entry(url : &str, stale : Option<CacheEntry>, status : u16, headers : &HeaderMap, body : String) -> (Option<CacheEntry>, (u16, String)) {
+        match stale {
+            Some(mut entry) if status == 304 => {
+                entry.fetched = Utc::now().timestamp();
+                let body = entry.body.clone();
+                (Some(entry), (200, body))
+            }
+            _ => {
+                if (200..300).contains(&status) {
+                    let header = |name| headers.get(name).and_then(|v : &HeaderValue| v.to_str().ok()).map(|v| v.to_string());
+                    let entry  = CacheEntry {
+                        url           : url.to_string(),
+                        etag          : header(ETAG),
+                        last_modified : header(LAST_MODIFIED),
+                        fetched       : Utc::now().timestamp(),
+                        body          : body.clone()
+                    };
+                    (Some(entry), (status, body))
+                } else {
+                    (None, (status, body))
+                }
+            }
+        }
+    }
+
+    // The directory and file to write an entry to, and the data to write
+    fn encode(&self, entry : &CacheEntry) -> DTResult<(PathBuf, PathBuf, String)> {
+        let path = self.path_for(&entry.url);
+        let dir  = path.parent().unwrap_or(&self.dir).to_path_buf();
+        let data = serde_json::to_string(entry).map_err(|e| DatatrackerError::CacheError(path.clone(), e.into()))?;
+        Ok((dir, path, data))
+    }
+}
+
+// =================================================================================================
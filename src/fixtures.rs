@@ -0,0 +1,155 @@
+// Copyright (C) 2020 University of Glasgow
+//
+// Redistribution and use in source and binary forms, with or without
+// modification, are permitted provided that the following conditions
+// are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice,
+//    this list of conditions and the following disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright
+//    notice, this list of conditions and the following disclaimer in the
+//    documentation and/or other materials provided with the distribution.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+// AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+// IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE
+// ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE
+// LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR
+// CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF
+// SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS
+// INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN
+// CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE)
+// ARISING IN ANY WAY OUT OF THE USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE
+// POSSIBILITY OF SUCH DAMAGE.
+//
+// SPDX-License-Identifier: BSD-2-Clause
+
+// Record and replay of Datatracker responses. In record mode, every request
+// made by a Datatracker is sent to the server as usual, and the URL, status
+// code, and JSON response are saved in a fixture file. In replay mode, those
+// fixture files are served back without accessing the network. This allows
+// tests to run offline, and to give the same result every time.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::api::*;
+
+// =================================================================================================
+
+// Filenames longer than this are shortened, to stay well within the NAME_MAX limit of
+// common filesystems. Query URLs with many parameters can easily exceed it otherwise.
+const MAX_FILENAME_LEN : usize = 200;
+const HASHED_PREFIX_LEN : usize = 100;
+
+// Escape characters that are not safe in a filename as _XX, where XX is the hex value
+// of the byte, so distinct strings always map to distinct filenames.
+pub(crate) fn escape_filename(s : &str) -> String {
+    let mut name = String::with_capacity(s.len() + 5);
+    for b in s.bytes() {
+        match b {
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'-' | b'.' | b'@' => name.push(b as char),
+            _ => name.push_str(&format!("_{:02X}", b))
+        }
+    }
+    name
+}
+
+// The filename used to store the response for a URL path, such as "/api/v1/person/person/?name=Colin%20Perkins".
+// Long names are replaced by a readable prefix followed by the SHA-256 digest of the URL.
+pub(crate) fn url_to_filename(url : &str) -> String {
+    let name = escape_filename(url.trim_start_matches("/api/v1/"));
+    if name.len() + ".json".len() <= MAX_FILENAME_LEN {
+        format!("{}.json", name)
+    } else {
+        let digest = Sha256::digest(url.as_bytes()).iter().map(|b| format!("{:02x}", b)).collect::<String>();
+        format!("{}-{}.json", &name[..HASHED_PREFIX_LEN], digest)
+    }
+}
+
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum FixtureMode {
+    Record,
+    Replay
+}
+
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Fixture {
+    pub url    : String,
+    pub status : u16,
+    pub body   : String
+}
+
+
+#[derive(Debug)]
+pub struct Fixtures {
+    mode : FixtureMode,
+    dir  : PathBuf
+}
+
+
+impl Fixtures {
+    pub fn new(mode : FixtureMode, dir : &Path) -> Self {
+        Fixtures {
+            mode,
+            dir : dir.to_path_buf()
+        }
+    }
+
+    pub fn mode(&self) -> FixtureMode {
+        self.mode
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn path_for(&self, url : &str) -> PathBuf {
+        self.dir.join(url_to_filename(url))
+    }
+
+    pub fn load(&self, url : &str) -> DTResult<Fixture> {
+        let path = self.path_for(url);
+        let data = fs::read_to_string(&path).map_err(|e| DatatrackerError::FixtureError(path.clone(), e))?;
+        serde_json::from_str(&data).map_err(|e| DatatrackerError::FixtureError(path, e.into()))
+    }
+
+    pub fn save(&self, url : &str, status : u16, body : &str) -> DTResult<()> {
+        let (path, data) = self.encode(url, status, body)?;
+        fs::create_dir_all(&self.dir).map_err(|e| DatatrackerError::FixtureError(self.dir.clone(), e))?;
+        fs::write(&path, data).map_err(|e| DatatrackerError::FixtureError(path, e))
+    }
+
+    // As load(), but without blocking the async runtime
+    pub(crate) async fn load_async(&self, url : &str) -> DTResult<Fixture> {
+        let path = self.path_for(url);
+        let data = tokio::fs::read_to_string(&path).await.map_err(|e| DatatrackerError::FixtureError(path.clone(), e))?;
+        serde_json::from_str(&data).map_err(|e| DatatrackerError::FixtureError(path, e.into()))
+    }
+
+    // As save(), but without blocking the async runtime
+    pub(crate) async fn save_async(&self, url : &str, status : u16, body : &str) -> DTResult<()> {
+        let (path, data) = self.encode(url, status, body)?;
+        tokio::fs::create_dir_all(&self.dir).await.map_err(|e| DatatrackerError::FixtureError(self.dir.clone(), e))?;
+        tokio::fs::write(&path, data).await.map_err(|e| DatatrackerError::FixtureError(path, e))
+    }
+
+    fn encode(&self, url : &str, status : u16, body : &str) -> DTResult<(PathBuf, String)> {
+        let path    = self.path_for(url);
+        let fixture = Fixture {
+            url    : url.to_string(),
+            status,
+            body   : body.to_string()
+        };
+        let data    = serde_json::to_string_pretty(&fixture).map_err(|e| DatatrackerError::FixtureError(path.clone(), e.into()))?;
+        Ok((path, data))
+    }
+}
+
+// =================================================================================================
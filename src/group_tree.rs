@@ -0,0 +1,170 @@
+// Copyright (C) 2019 University of Glasgow
+// 
+// Redistribution and use in source and binary forms, with or without
+// modification, are permitted provided that the following conditions 
+// are met:
+// 
+// 1. Redistributions of source code must retain the above copyright notice,
+//    this list of conditions and the following disclaimer.
+// 
+// 2. Redistributions in binary form must reproduce the above copyright
+//    notice, this list of conditions and the following disclaimer in the
+//    documentation and/or other materials provided with the distribution.
+// 
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+// AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+// IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE
+// ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE
+// LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR
+// CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF
+// SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS
+// INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN
+// CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE)
+// ARISING IN ANY WAY OUT OF THE USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE
+// POSSIBILITY OF SUCH DAMAGE.
+//
+// SPDX-License-Identifier: BSD-2-Clause
+
+
+// The hierarchy of groups, built from the parent of each group. The roots
+// are the groups with no parent, such as the IETF, IRTF, and IAB, with areas
+// below the IETF, and working groups and directorates below each area. For
+// example, to list the active working groups in an area:
+//   let tree   = dt.group_tree()?;
+//   let active = tree.subtree(&area.resource_uri, &[GroupStateUri::from_slug("active")]);
+
+use std::collections::{BTreeMap, HashSet};
+
+use serde_json::{json, Value};
+
+use crate::*;
+
+// =================================================================================================
+
+pub struct GroupTree {
+    groups   : BTreeMap<GroupUri, Group>,
+    children : BTreeMap<GroupUri, Vec<GroupUri>>
+}
+
+
+impl GroupTree {
+    pub fn new(groups : Vec<Group>) -> Self {
+        let groups = groups.into_iter().map(|g| (g.resource_uri.clone(), g)).collect::<BTreeMap<_, _>>();
+        let mut children : BTreeMap<GroupUri, Vec<GroupUri>> = BTreeMap::new();
+        for group in groups.values() {
+            if let Some(parent) = group.parent.as_ref().filter(|p| groups.contains_key(p) && *p != &group.resource_uri) {
+                children.entry(parent.clone()).or_default().push(group.resource_uri.clone());
+            }
+        }
+        for uris in children.values_mut() {
+            uris.sort_by(|a, b| groups[a].acronym.cmp(&groups[b].acronym));
+        }
+        GroupTree {groups, children}
+    }
+
+    pub fn group(&self, group_uri : &GroupUri) -> Option<&Group> {
+        self.groups.get(group_uri)
+    }
+
+    // The groups with no parent in the tree, ordered by acronym
+    pub fn roots(&self) -> Vec<&Group> {
+        let mut roots = self.groups.values().filter(|g| self.parent(g).is_none()).collect::<Vec<_>>();
+        roots.sort_by(|a, b| a.acronym.cmp(&b.acronym));
+        roots
+    }
+
+    // The children of a group, ordered by acronym
+    pub fn children(&self, group_uri : &GroupUri) -> Vec<&Group> {
+        match self.children.get(group_uri) {
+            Some(uris) => uris.iter().map(|uri| &self.groups[uri]).collect(),
+            None       => Vec::new()
+        }
+    }
+
+    // The parent of a group, its parent, and so on, nearest first
+    pub fn ancestors(&self, group_uri : &GroupUri) -> Vec<&Group> {
+        let mut seen      = HashSet::new();
+        let mut ancestors = Vec::new();
+        let mut current   = self.group(group_uri);
+        while let Some(parent) = current.and_then(|g| self.parent(g)) {
+            if !seen.insert(&parent.resource_uri) {
+                break;
+            }
+            ancestors.push(parent);
+            current = Some(parent);
+        }
+        ancestors
+    }
+
+    // The tree below a group, including only groups in one of the given
+    // states, or all groups if no states are given. Groups in other states
+    // are left out along with their children, but the root is always kept.
+    pub fn subtree(&self, group_uri : &GroupUri, states : &[GroupStateUri]) -> Option<GroupNode<'_>> {
+        let group = self.group(group_uri)?;
+        Some(self.node(group, states, &mut HashSet::new()))
+    }
+
+    // The tree below each of the roots, filtered as for subtree()
+    pub fn forest(&self, states : &[GroupStateUri]) -> Vec<GroupNode<'_>> {
+        self.roots().into_iter()
+                    .filter(|g| states.is_empty() || states.contains(&g.state))
+                    .map(|g| self.node(g, states, &mut HashSet::new()))
+                    .collect()
+    }
+
+    fn parent(&self, group : &Group) -> Option<&Group> {
+        group.parent.as_ref().filter(|p| *p != &group.resource_uri).and_then(|p| self.group(p))
+    }
+
+    fn node<'a>(&'a self, group : &'a Group, states : &[GroupStateUri], seen : &mut HashSet<&'a GroupUri>) -> GroupNode<'a> {
+        seen.insert(&group.resource_uri);
+        let mut children = Vec::new();
+        for child in self.children(&group.resource_uri) {
+            if (states.is_empty() || states.contains(&child.state)) && !seen.contains(&child.resource_uri) {
+                children.push(self.node(child, states, seen));
+            }
+        }
+        GroupNode {group, children}
+    }
+}
+
+// =================================================================================================
+
+pub struct GroupNode<'a> {
+    pub group    : &'a Group,
+    pub children : Vec<GroupNode<'a>>
+}
+
+
+impl<'a> GroupNode<'a> {
+    // The tree as indented text, one group per line, e.g.:
+    //   art: Applications and Real-Time Area (area, active)
+    //     avtcore: Audio/Video Transport Core Maintenance (wg, active)
+    pub fn to_text(&self) -> String {
+        let mut text = String::new();
+        self.write_text(&mut text, 0);
+        text
+    }
+
+    // The tree as JSON, with the acronym, name, type, and state of each
+    // group, and a list of its children
+    pub fn to_json(&self) -> Value {
+        json!({
+            "acronym"  : self.group.acronym,
+            "name"     : self.group.name,
+            "type"     : self.group.group_type.slug(),
+            "state"    : self.group.state.slug(),
+            "children" : self.children.iter().map(|c| c.to_json()).collect::<Vec<_>>()
+        })
+    }
+
+    fn write_text(&self, text : &mut String, depth : usize) {
+        text.push_str(&format!("{}{}: {} ({}, {})\n", "  ".repeat(depth), self.group.acronym, self.group.name,
+                               self.group.group_type.slug().unwrap_or("?"), self.group.state.slug().unwrap_or("?")));
+        for child in &self.children {
+            child.write_text(text, depth + 1);
+        }
+    }
+}
+
+// =================================================================================================
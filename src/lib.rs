@@ -47,291 +47,1590 @@
 //   RFC 7760 "Statement of Work for Extensions to the IETF Datatracker for Author Statistics"
 
 mod api;
+mod async_client;
+mod cache;
+mod fixtures;
+mod group_tree;
+mod query;
+mod resource;
+mod retry;
+mod state_machine;
 
 pub use api::*;
 pub use api::email::*;
 pub use api::person::*;
 pub use api::group::*;
 pub use api::document::*;
+pub use api::docevent::*;
+pub use api::name::*;
+pub use api::submission::*;
+pub use api::ballot::*;
+pub use api::milestone::*;
+pub use api::groupevent::*;
+pub use async_client::*;
+pub use cache::*;
+pub use fixtures::*;
+pub use group_tree::*;
+pub use query::*;
+pub use resource::*;
+pub use retry::*;
+pub use state_machine::*;
+
+use std::collections::{HashSet, VecDeque};
+use std::path::Path;
+use std::thread;
+use std::time::Duration;
 
 use chrono::prelude::*;
-
 use serde::Deserialize;
 
+use cache::CacheCheck;
+
 // =================================================================================================================================
 // IETF Datatracker API:
 
+const DEFAULT_BASE_URL   : &str = "https://datatracker.ietf.org";
+const DEFAULT_USER_AGENT : &str = concat!("ietfdata-rs/", env!("CARGO_PKG_VERSION"));
+
+pub struct DatatrackerBuilder {
+    base_url        : String,
+    user_agent      : String,
+    timeout         : Option<Duration>,
+    connect_timeout : Option<Duration>,
+    client          : Option<reqwest::blocking::Client>,
+    async_client    : Option<reqwest::Client>,
+    fixtures        : Option<Fixtures>,
+    cache           : Option<Cache>,
+    retry           : RetryPolicy,
+    rate_limit      : Option<f64>
+}
+
+
+impl DatatrackerBuilder {
+    pub fn new() -> Self {
+        DatatrackerBuilder {
+            base_url        : DEFAULT_BASE_URL.to_string(),
+            user_agent      : DEFAULT_USER_AGENT.to_string(),
+            timeout         : None,
+            connect_timeout : None,
+            client          : None,
+            async_client    : None,
+            fixtures        : None,
+            cache           : None,
+            retry           : RetryPolicy::new(),
+            rate_limit      : None
+        }
+    }
+
+    // The base URL of the Datatracker instance, for example "https://datatracker.ietf.org"
+    // or a local development instance. Any trailing slash is ignored.
+    pub fn base_url(mut self, base_url : &str) -> Self {
+        self.base_url = base_url.trim_end_matches('/').to_string();
+        self
+    }
+
+    pub fn user_agent(mut self, user_agent : &str) -> Self {
+        self.user_agent = user_agent.to_string();
+        self
+    }
+
+    pub fn timeout(mut self, timeout : Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    pub fn connect_timeout(mut self, timeout : Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    // Use a preconfigured client. The client is used as is, and the user
+    // agent and timeouts set on this builder are ignored.
+    pub fn client(mut self, client : reqwest::blocking::Client) -> Self {
+        self.client = Some(client);
+        self
+    }
+
+    // As client(), but for the client used by build_async()
+    pub fn async_client(mut self, client : reqwest::Client) -> Self {
+        self.async_client = Some(client);
+        self
+    }
+
+    // Save every response received in the given directory, for later replay.
+    pub fn record_fixtures(mut self, dir : &Path) -> Self {
+        self.fixtures = Some(Fixtures::new(FixtureMode::Record, dir));
+        self
+    }
+
+    // Serve responses from fixtures previously saved in the given directory,
+    // rather than from the network.
+    pub fn replay_fixtures(mut self, dir : &Path) -> Self {
+        self.fixtures = Some(Fixtures::new(FixtureMode::Replay, dir));
+        self
+    }
+
+    // Cache responses on disk. See the Cache type for configuration.
+    pub fn cache(mut self, cache : Cache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    // How to retry requests that fail with transient errors. The default
+    // is RetryPolicy::new(); use RetryPolicy::none() to disable retries.
+    pub fn retry(mut self, retry : RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    // Limit the rate at which requests are sent to the server. This applies
+    // to all requests, including retries and fetching pages of a list. The
+    // rate must be at least one request per day, or build() fails with
+    // InvalidRateLimit.
+    pub fn rate_limit(mut self, requests_per_second : f64) -> Self {
+        self.rate_limit = Some(requests_per_second);
+        self
+    }
+
+    fn default_headers(&self) -> DTResult<reqwest::header::HeaderMap> {
+        let user_agent = reqwest::header::HeaderValue::from_str(&self.user_agent)
+                            .map_err(|_| DatatrackerError::InvalidUserAgent(self.user_agent.clone()))?;
+        let mut headers = reqwest::header::HeaderMap::new();
+        headers.insert(reqwest::header::USER_AGENT, user_agent);
+        Ok(headers)
+    }
+
+    pub fn build(self) -> DTResult<Datatracker> {
+        let connection = match self.client {
+            Some(ref client) => client.clone(),
+            None => {
+                let mut builder = reqwest::blocking::Client::builder().default_headers(self.default_headers()?);
+                if let Some(timeout) = self.timeout {
+                    builder = builder.timeout(timeout);
+                }
+                if let Some(timeout) = self.connect_timeout {
+                    builder = builder.connect_timeout(timeout);
+                }
+                builder.build()?
+            }
+        };
+
+        Ok(Datatracker {
+            connection,
+            base_url     : self.base_url,
+            fixtures     : self.fixtures,
+            cache        : self.cache,
+            retry        : self.retry,
+            rate_limiter : self.rate_limit.map(RateLimiter::new).transpose()?
+        })
+    }
+
+    pub fn build_async(self) -> DTResult<AsyncDatatracker> {
+        let connection = match self.async_client {
+            Some(ref client) => client.clone(),
+            None => {
+                let mut builder = reqwest::Client::builder().default_headers(self.default_headers()?);
+                if let Some(timeout) = self.timeout {
+                    builder = builder.timeout(timeout);
+                }
+                if let Some(timeout) = self.connect_timeout {
+                    builder = builder.connect_timeout(timeout);
+                }
+                builder.build()?
+            }
+        };
+
+        Ok(AsyncDatatracker {
+            connection,
+            base_url     : self.base_url,
+            fixtures     : self.fixtures,
+            cache        : self.cache,
+            retry        : self.retry,
+            rate_limiter : self.rate_limit.map(RateLimiter::new).transpose()?
+        })
+    }
+}
+
+
+impl Default for DatatrackerBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+
 pub struct Datatracker {
-    connection : reqwest::Client
+    connection   : reqwest::blocking::Client,
+    base_url     : String,
+    fixtures     : Option<Fixtures>,
+    cache        : Option<Cache>,
+    retry        : RetryPolicy,
+    rate_limiter : Option<RateLimiter>
 }
 
 
 impl Datatracker {
-    fn retrieve<T>(&self, url : &str) -> DTResult<T>
+    fn retrieve<T>(&self, path : &str) -> DTResult<T>
         where for<'de> T: Deserialize<'de> 
     {
-        let mut res = self.connection.get(url).send()?;
-        if res.status().is_success() {
-            Ok(res.json()?)
-        } else {
-            Err(DatatrackerError::NotFound)
+        if !path.starts_with("/api/") {
+            return Err(DatatrackerError::InvalidUri(path.to_string()));
         }
+
+        let (status, body) = match self.fixtures {
+            Some(ref fixtures) if fixtures.mode() == FixtureMode::Replay => {
+                let fixture = fixtures.load(path)?;
+                (fixture.status, fixture.body)
+            }
+            _ => {
+                let (status, body) = self.fetch(path)?;
+                if let Some(ref fixtures) = self.fixtures {
+                    fixtures.save(path, status, &body)?;
+                }
+                (status, body)
+            }
+        };
+        decode_response(&format!("{}{}", self.base_url, path), status, &body)
     }
 
 
+    // Fetch a URL path from the server, or from the cache if enabled
+    fn fetch(&self, path : &str) -> DTResult<(u16, String)> {
+        let url   = format!("{}{}", self.base_url, path);
+        let stale = match self.cache {
+            Some(ref cache) => {
+                match cache.check(&url)? {
+                    CacheCheck::Hit(body)         => return Ok((200, body)),
+                    CacheCheck::Revalidate(entry) => Some(entry),
+                    CacheCheck::Miss              => None
+                }
+            }
+            None => None
+        };
+
+        let mut attempt = 0;
+        let res = loop {
+            if let Some(ref limiter) = self.rate_limiter {
+                thread::sleep(limiter.reserve());
+            }
+            let mut req = self.connection.get(&url);
+            if let Some(ref entry) = stale {
+                req = req.headers(Cache::revalidation_headers(entry));
+            }
+            match req.send() {
+                Ok(res) if self.retry.should_retry(attempt) && RetryPolicy::is_retryable_status(res.status()) => {
+                    match self.retry.delay(attempt, retry_after(res.headers())) {
+                        Some(delay) => thread::sleep(delay),
+                        None        => return Err(DatatrackerError::RateLimited { url, retry_after : retry_after(res.headers()) })
+                    }
+                }
+                Ok(res) => {
+                    break res;
+                }
+                Err(e) if self.retry.should_retry(attempt) && RetryPolicy::is_retryable_error(&e) => {
+                    thread::sleep(self.retry.backoff(attempt));
+                }
+                Err(e) => {
+                    return Err(e.into());
+                }
+            }
+            attempt += 1;
+        };
+
+        if res.status() == reqwest::StatusCode::TOO_MANY_REQUESTS {
+            return Err(DatatrackerError::RateLimited { url, retry_after : retry_after(res.headers()) });
+        }
+        let status  = res.status().as_u16();
+        let headers = res.headers().clone();
+        let body    = res.text()?;
+
+        match self.cache {
+            Some(ref cache) => cache.update(&url, stale, status, &headers, body),
+            None            => Ok((status, body))
+        }
+    }
+
+
+    // Connect to the main IETF Datatracker, with default settings. Use
+    // Datatracker::builder() to connect to a different instance.
     pub fn new() -> Self {
-        Datatracker {
-            connection : reqwest::Client::new()
+        DatatrackerBuilder::new().build().expect("Cannot initialise Datatracker connection")
+    }
+
+
+    pub fn builder() -> DatatrackerBuilder {
+        DatatrackerBuilder::new()
+    }
+
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+
+    pub fn fixtures(&self) -> Option<&Fixtures> {
+        self.fixtures.as_ref()
+    }
+
+
+    pub fn cache(&self) -> Option<&Cache> {
+        self.cache.as_ref()
+    }
+
+
+    // Fetch the resource identified by a URI, for example:
+    //   dt.get(&PersonUri("/api/v1/person/person/20209/".to_string()))
+    pub fn get<U: ResourceUri>(&self, uri : &U) -> DTResult<U::Resource> {
+        self.retrieve::<U::Resource>(uri.as_str())
+    }
+
+
+    // Start a query on the list endpoint for type T, for example:
+    //   dt.query::<Person>().contains("name", "Perkins").fetch()
+    pub fn query<T: Endpoint>(&self) -> Query<'_, Datatracker, T> {
+        Query::new(self)
+    }
+
+
+    // ----------------------------------------------------------------------------------------------------------------------------
+    // Datatracker API endpoints returning information about email addresses:
+    // * https://datatracker.ietf.org/api/v1/person/email/csp@csperkins.org/
+    // * https://datatracker.ietf.org/api/v1/person/historicalemail/
+
+    pub fn email(&self, email_uri: &EmailUri) -> DTResult<Email> {
+        self.get(email_uri)
+    }
+
+    pub fn email_from_address(&self, email_addr : &str) -> DTResult<Email> {
+        let url = format!("/api/v1/person/email/{}/", email_addr);
+        self.retrieve::<Email>(&url)
+    }
+
+
+    pub fn email_history_for_address<'a>(&'a self, email_addr : &'a str) -> DTResult<PaginatedList<'a, HistoricalEmail>> {
+        self.query::<HistoricalEmail>().exact("address", email_addr).fetch()
+    }
+
+
+    pub fn email_history_for_person<'a>(&'a self, person : &'a Person) -> DTResult<PaginatedList<'a, HistoricalEmail>> {
+        self.query::<HistoricalEmail>().exact("person", person.id).fetch()
+    }
+
+
+    // ----------------------------------------------------------------------------------------------------------------------------
+    // Datatracker API endpoints returning information about people:
+    // * https://datatracker.ietf.org/api/v1/person/person/20209/
+    // * https://datatracker.ietf.org/api/v1/person/person/
+    // * https://datatracker.ietf.org/api/v1/person/historicalperson/
+    // * https://datatracker.ietf.org/api/v1/person/alias/
+
+    pub fn person(&self, person_uri : &PersonUri) -> DTResult<Person> {
+        self.get(person_uri)
+    }
+
+
+    pub fn person_from_email(&self, email : &EmailUri) -> DTResult<Person> {
+        let person = self.email(email)?.person;
+        self.person(&person)
+    }
+
+    pub fn person_from_email_address(&self, email_addr : &str) -> DTResult<Person> {
+        let person = self.email_from_address(email_addr)?.person;
+        self.person(&person)
+    }
+
+
+    pub fn person_aliases<'a>(&'a self, person : &'a Person) -> DTResult<PaginatedList<'a, PersonAlias>> {
+        self.query::<PersonAlias>().exact("person", person.id).fetch()
+    }
+
+
+    pub fn person_history<'a>(&'a self, person : &'a Person) -> DTResult<PaginatedList<'a, HistoricalPerson>> {
+        self.query::<HistoricalPerson>().exact("id", person.id).fetch()
+    }
+
+
+    // The authors of a document, in order
+    pub fn document_authors<'a>(&'a self, doc : &Document) -> DTResult<PaginatedList<'a, DocumentAuthor>> {
+        self.query::<DocumentAuthor>().document(doc).order_by("order").fetch()
+    }
+
+
+    // The documents a person is an author of, as one authorship record for
+    // each document, giving the document and the affiliation and email address
+    // used. Use query::<DocumentAuthor>() and email() to find documents by a
+    // particular email address.
+    pub fn documents_authored_by<'a>(&'a self, person : &Person) -> DTResult<PaginatedList<'a, DocumentAuthor>> {
+        self.query::<DocumentAuthor>().person(&person.resource_uri).fetch()
+    }
+
+
+    // Use query::<Person>() for other filters
+    pub fn people<'a>(&'a self) -> DTResult<PaginatedList<'a, Person>> {
+        self.query::<Person>().fetch()
+    }
+
+
+    pub fn people_with_name<'a>(&'a self, name: &'a str) -> DTResult<PaginatedList<'a, Person>> {
+        self.query::<Person>().exact("name", name).fetch()
+    }
+
+
+    pub fn people_with_name_containing<'a>(&'a self, name_contains: &'a str) -> DTResult<PaginatedList<'a, Person>> {
+        self.query::<Person>().contains("name", name_contains).fetch()
+    }
+
+
+
+    // ----------------------------------------------------------------------------------------------------------------------------
+    // Datatracker API endpoints returning information about documents:
+    // * https://datatracker.ietf.org/api/v1/doc/document/                        - list of documents
+    // * https://datatracker.ietf.org/api/v1/doc/document/draft-ietf-avt-rtp-new/ - info about document
+    // * https://datatracker.ietf.org/api/v1/doc/docalias/?name=/                 - draft that became the given RFC
+    // * https://datatracker.ietf.org/api/v1/doc/state/                           - Types of state a document can be in
+    // * https://datatracker.ietf.org/api/v1/doc/statetype/                       - Possible types of state for a document
+    // * https://datatracker.ietf.org/api/v1/doc/docevent/                        - list of document events
+    // * https://datatracker.ietf.org/api/v1/doc/docevent/?doc=...                - events for a document
+    // * https://datatracker.ietf.org/api/v1/doc/docevent/?by=...                 - events by a person (as /api/v1/person/person)
+    // * https://datatracker.ietf.org/api/v1/doc/docevent/?time=...               - events by time
+    // * https://datatracker.ietf.org/api/v1/doc/documentauthor/?document=...     - authors of a document
+    // * https://datatracker.ietf.org/api/v1/doc/documentauthor/?person=...       - documents by person (as /api/v1/person/person)
+    // * https://datatracker.ietf.org/api/v1/doc/documentauthor/?email=...        - documents by person with particular email
+    //   https://datatracker.ietf.org/api/v1/doc/dochistory/
+    //   https://datatracker.ietf.org/api/v1/doc/dochistoryauthor/
+    //   https://datatracker.ietf.org/api/v1/doc/docreminder/
+    //   https://datatracker.ietf.org/api/v1/doc/documenturl/
+    // * https://datatracker.ietf.org/api/v1/doc/statedocevent/                   - subset of /api/v1/doc/docevent/; same parameters
+    // * https://datatracker.ietf.org/api/v1/doc/ballotdocevent/                  -               "                "
+    // * https://datatracker.ietf.org/api/v1/doc/newrevisiondocevent/             -               "                "
+    // * https://datatracker.ietf.org/api/v1/doc/submissiondocevent/              -               "                "
+    // * https://datatracker.ietf.org/api/v1/doc/writeupdocevent/                 -               "                "
+    // * https://datatracker.ietf.org/api/v1/doc/consensusdocevent/               -               "                "
+    // * https://datatracker.ietf.org/api/v1/doc/ballotpositiondocevent/          -               "                "
+    // * https://datatracker.ietf.org/api/v1/doc/reviewrequestdocevent/           -               "                "
+    // * https://datatracker.ietf.org/api/v1/doc/lastcalldocevent/                -               "                "
+    // * https://datatracker.ietf.org/api/v1/doc/telechatdocevent/                -               "                "
+    // * https://datatracker.ietf.org/api/v1/doc/relateddocument/?source=...      - documents that source draft relates to (references, replaces, etc)
+    // * https://datatracker.ietf.org/api/v1/doc/relateddocument/?target=...      - documents that relate to target draft
+    // * https://datatracker.ietf.org/api/v1/doc/ballottype/                      - Types of ballot that can be issued on a document
+    //   https://datatracker.ietf.org/api/v1/doc/relateddochistory/
+    //   https://datatracker.ietf.org/api/v1/doc/initialreviewdocevent/
+    // * https://datatracker.ietf.org/api/v1/doc/deletedevent/
+    // * https://datatracker.ietf.org/api/v1/doc/addedmessageevent/
+    // * https://datatracker.ietf.org/api/v1/doc/editedauthorsdocevent/
+
+    pub fn document(&self, doc_uri : &DocumentUri) -> DTResult<Document> {
+        self.get(doc_uri)
+    }
+
+
+    // The document with the given name, e.g., "draft-ietf-avt-rtp-new"
+    pub fn document_from_draft(&self, name : &str) -> DTResult<Document> {
+        self.get(&DocumentUri::from_name(name))
+    }
+
+
+    // The document that was published as the given RFC
+    pub fn document_from_rfc(&self, rfc : u64) -> DTResult<Document> {
+        self.document_from_alias(&format!("rfc{}", rfc))
+    }
+
+
+    // The document with the given alias, e.g., "rfc3550" gives the draft
+    // that was published as RFC 3550. Use documents_from_alias() for
+    // subseries names that are aliases of several documents.
+    pub fn document_from_alias(&self, name : &str) -> DTResult<Document> {
+        match self.doc_aliases(name)?.next() {
+            Some(alias) => self.document(&alias?.document),
+            None        => Err(DatatrackerError::NotFound)
+        }
+    }
+
+
+    // The documents with the given alias, e.g., "bcp14" gives the RFCs
+    // in BCP 14
+    pub fn documents_from_alias(&self, name : &str) -> DTResult<Vec<Document>> {
+        self.doc_aliases(name)?.map(|alias| self.document(&alias?.document)).collect()
+    }
+
+
+    // The RFC number of a document, if it has been published as an RFC
+    pub fn rfc_number(&self, doc : &Document) -> DTResult<Option<u64>> {
+        for alias in self.doc_aliases_for_document(doc)? {
+            let alias = alias?;
+            if let Some(rfc) = alias.name.strip_prefix("rfc").and_then(|n| n.parse().ok()) {
+                return Ok(Some(rfc));
+            }
+        }
+        Ok(None)
+    }
+
+
+    pub fn doc_alias(&self, alias_uri : &DocAliasUri) -> DTResult<DocAlias> {
+        self.get(alias_uri)
+    }
+
+
+    // The aliases with the given name
+    pub fn doc_aliases<'a>(&'a self, name : &str) -> DTResult<PaginatedList<'a, DocAlias>> {
+        self.query::<DocAlias>().exact("name", name).fetch()
+    }
+
+
+    // All the aliases of a document
+    pub fn doc_aliases_for_document<'a>(&'a self, doc : &Document) -> DTResult<PaginatedList<'a, DocAlias>> {
+        self.query::<DocAlias>().exact("document", doc.id).fetch()
+    }
+
+
+    // A query on the list of documents, that can be filtered, for example:
+    //   dt.documents().group(&group).doc_type(&DocType::Draft).since(time).fetch()
+    pub fn documents(&self) -> Query<'_, Datatracker, Document> {
+        self.query::<Document>()
+    }
+
+
+    // The document that an alias refers to, e.g., the target of a RelatedDocument
+    pub fn document_for_alias(&self, alias_uri : &DocAliasUri) -> DTResult<Document> {
+        let alias = self.doc_alias(alias_uri)?;
+        self.document(&alias.document)
+    }
+
+
+    // The relationships from a document to other documents, e.g., the
+    // documents that it replaces, obsoletes, or references
+    pub fn related_documents_from<'a>(&'a self, doc : &Document) -> DTResult<PaginatedList<'a, RelatedDocument>> {
+        self.query::<RelatedDocument>().source(doc).fetch()
+    }
+
+
+    // The relationships from other documents to a document, via any of its
+    // aliases, e.g., the documents that replace, obsolete, or reference it
+    pub fn related_documents_to<'a>(&'a self, doc : &Document) -> DTResult<PaginatedList<'a, RelatedDocument>> {
+        let aliases = self.doc_alias_ids(doc)?;
+        if aliases.is_empty() {
+            // An empty target__in filter is rejected by the Datatracker
+            return Ok(PaginatedList::empty(self));
+        }
+        self.query::<RelatedDocument>().is_in("target", &aliases).fetch()
+    }
+
+
+    fn doc_alias_ids(&self, doc : &Document) -> DTResult<Vec<u64>> {
+        self.doc_aliases_for_document(doc)?.map(|alias| alias.map(|a| a.id)).collect()
+    }
+
+
+    // The normative and informative references from a document to others
+    pub fn references<'a>(&'a self, doc : &Document) -> DTResult<PaginatedList<'a, RelatedDocument>> {
+        self.query::<RelatedDocument>().source(doc).relationships(&Relationship::references()).fetch()
+    }
+
+
+    // The normative and informative references to a document from others
+    pub fn referenced_by<'a>(&'a self, doc : &Document) -> DTResult<PaginatedList<'a, RelatedDocument>> {
+        let aliases = self.doc_alias_ids(doc)?;
+        if aliases.is_empty() {
+            return Ok(PaginatedList::empty(self));
+        }
+        self.query::<RelatedDocument>().is_in("target", &aliases).relationships(&Relationship::references()).fetch()
+    }
+
+
+    // The documents related to a document by any of the given relationships,
+    // directly or indirectly, nearest first. For example, the RFCs that
+    // obsolete or update an RFC, and the RFCs that obsolete or update those:
+    //   dt.related_closure(&rfc, &[Relationship::Obsoletes, Relationship::Updates], RelationshipDirection::Incoming)
+    pub fn related_closure(&self, doc : &Document, relationships : &[Relationship], direction : RelationshipDirection) -> DTResult<Vec<Document>> {
+        let mut seen   = HashSet::new();
+        let mut queue  = VecDeque::from(self.related_uris(doc, relationships, direction)?);
+        let mut result = Vec::new();
+        seen.insert(doc.resource_uri.clone());
+        while let Some(uri) = queue.pop_front() {
+            if seen.insert(uri.clone()) {
+                let related = self.document(&uri)?;
+                queue.extend(self.related_uris(&related, relationships, direction)?);
+                result.push(related);
+            }
+        }
+        Ok(result)
+    }
+
+    fn related_uris(&self, doc : &Document, relationships : &[Relationship], direction : RelationshipDirection) -> DTResult<Vec<DocumentUri>> {
+        match direction {
+            RelationshipDirection::Outgoing => {
+                self.query::<RelatedDocument>()
+                    .source(doc)
+                    .relationships(relationships)
+                    .fetch()?
+                    .map(|rel| Ok(self.doc_alias(&rel?.target)?.document))
+                    .collect()
+            }
+            RelationshipDirection::Incoming => {
+                let aliases = self.doc_alias_ids(doc)?;
+                if aliases.is_empty() {
+                    return Ok(Vec::new());
+                }
+                self.query::<RelatedDocument>()
+                    .is_in("target", &aliases)
+                    .relationships(relationships)
+                    .fetch()?
+                    .map(|rel| rel.map(|r| r.source))
+                    .collect()
+            }
+        }
+    }
+
+
+    // The drafts that a document replaced, directly or indirectly, nearest
+    // first. For a document with a single line of predecessors, the last is
+    // the original individual draft.
+    pub fn replaces_chain(&self, doc : &Document) -> DTResult<Vec<Document>> {
+        self.related_closure(doc, &[Relationship::Replaces], RelationshipDirection::Outgoing)
+    }
+
+
+    pub fn doc_relationship_names<'a>(&'a self) -> DTResult<PaginatedList<'a, DocRelationshipName>> {
+        self.query::<DocRelationshipName>().fetch()
+    }
+
+
+    pub fn doc_event(&self, event_uri : &DocEventUri) -> DTResult<BaseDocEvent> {
+        self.get(event_uri)
+    }
+
+
+    // A query on the list of document events, that can be filtered, for example:
+    //   dt.doc_events().by(&person_uri).since(time).fetch()
+    // Use fetch_typed() to fetch each event as its most specific type.
+    pub fn doc_events(&self) -> Query<'_, Datatracker, BaseDocEvent> {
+        self.query::<BaseDocEvent>()
+    }
+
+
+    // The history of a document, oldest event first
+    pub fn doc_events_for_document(&self, doc : &Document) -> DTResult<Vec<DocEvent>> {
+        self.doc_events().doc(doc).order_by("time").fetch_typed()
+    }
+
+
+    pub fn deleted_event(&self, event_uri : &DeletedEventUri) -> DTResult<DeletedEvent> {
+        self.get(event_uri)
+    }
+
+
+    // A query on the list of deleted objects, for example:
+    //   dt.deleted_events().exact("content_type", 29).since(time).fetch()
+    pub fn deleted_events(&self) -> Query<'_, Datatracker, DeletedEvent> {
+        self.query::<DeletedEvent>()
+    }
+
+
+    pub fn doc_state(&self, state_uri: &DocStateUri) -> DTResult<DocState> {
+        self.get(state_uri)
+    }
+
+
+    pub fn doc_states<'a>(&'a self) -> DTResult<PaginatedList<'a, DocState>> {
+        self.query::<DocState>().fetch()
+    }
+
+
+    pub fn doc_state_type(&self, state_type_uri: &DocStateTypeUri) -> DTResult<DocStateType> {
+        self.get(state_type_uri)
+    }
+
+
+    pub fn doc_state_types<'a>(&'a self) -> DTResult<PaginatedList<'a, DocStateType>> {
+        self.query::<DocStateType>().fetch()
+    }
+
+
+    pub fn ballot_type(&self, ballot_type_uri : &BallotTypeUri) -> DTResult<BallotType> {
+        self.get(ballot_type_uri)
+    }
+
+
+    pub fn ballot_types<'a>(&'a self) -> DTResult<PaginatedList<'a, BallotType>> {
+        self.query::<BallotType>().fetch()
+    }
+
+
+    pub fn ballot_position_names<'a>(&'a self) -> DTResult<PaginatedList<'a, BallotPositionName>> {
+        self.query::<BallotPositionName>().fetch()
+    }
+
+
+    // The most recent ballot on a document, if any, with the latest position
+    // of each Area Director
+    pub fn current_ballot(&self, doc : &Document) -> DTResult<Option<Ballot>> {
+        let events = self.query::<BallotDocEvent>().doc(doc).order_by("-time").fetch()?.collect::<DTResult<Vec<_>>>()?;
+        let (ballot, closed) = match latest_ballot(events) {
+            Some(latest) => latest,
+            None         => return Ok(None)
+        };
+        let positions = self.query::<BallotPositionDocEvent>().ballot(&ballot).order_by("time").fetch()?.collect::<DTResult<Vec<_>>>()?;
+        Ok(Some(Ballot::new(ballot, closed, positions)))
+    }
+
+
+    // The state machines for all types of document state
+    pub fn state_machine(&self) -> DTResult<StateMachine> {
+        let types  = self.doc_state_types()?.collect::<DTResult<Vec<_>>>()?;
+        let states = self.doc_states()?.collect::<DTResult<Vec<_>>>()?;
+        Ok(StateMachine::new(types, states))
+    }
+
+
+    // ----------------------------------------------------------------------------------------------------------------------------
+    // Datatracker API endpoints returning information about draft submissions:
+    // * https://datatracker.ietf.org/api/v1/submit/submission/                   - list of submissions
+    // * https://datatracker.ietf.org/api/v1/submit/submission/?draft=...         - submissions of a document
+    // * https://datatracker.ietf.org/api/v1/submit/submissioncheck/?submission=...
+    // * https://datatracker.ietf.org/api/v1/submit/submissionevent/?submission=...
+
+    pub fn submission(&self, submission_uri : &SubmissionUri) -> DTResult<Submission> {
+        self.get(submission_uri)
+    }
+
+
+    // A query on the list of submissions, that can be filtered, for example:
+    //   dt.submissions().group(&group).submitted_since(date).fetch()
+    pub fn submissions(&self) -> Query<'_, Datatracker, Submission> {
+        self.query::<Submission>()
+    }
+
+
+    // The submissions of each revision of a document, oldest first
+    pub fn submissions_for_document<'a>(&'a self, doc : &Document) -> DTResult<PaginatedList<'a, Submission>> {
+        self.submissions().document(doc).order_by("submission_date").fetch()
+    }
+
+
+    pub fn submission_checks<'a>(&'a self, submission : &Submission) -> DTResult<PaginatedList<'a, SubmissionCheck>> {
+        self.query::<SubmissionCheck>().exact("submission", submission.id).fetch()
+    }
+
+
+    pub fn submission_events<'a>(&'a self, submission : &Submission) -> DTResult<PaginatedList<'a, SubmissionEvent>> {
+        self.query::<SubmissionEvent>().exact("submission", submission.id).order_by("time").fetch()
+    }
+
+
+    // ----------------------------------------------------------------------------------------------------------------------------
+    // Datatracker API endpoints returning information about names:
+    //   https://datatracker.ietf.org/api/v1/name/doctypename/
+    //   https://datatracker.ietf.org/api/v1/name/streamname/
+    //   https://datatracker.ietf.org/api/v1/name/dbtemplatetypename/
+    // * https://datatracker.ietf.org/api/v1/name/docrelationshipname/
+    //   https://datatracker.ietf.org/api/v1/name/doctagname/
+    //   https://datatracker.ietf.org/api/v1/name/docurltagname/
+    // * https://datatracker.ietf.org/api/v1/name/groupstatename/
+    //   https://datatracker.ietf.org/api/v1/name/formallanguagename/
+    //   https://datatracker.ietf.org/api/v1/name/timeslottypename/
+    //   https://datatracker.ietf.org/api/v1/name/liaisonstatementeventtypename/
+    //   https://datatracker.ietf.org/api/v1/name/stdlevelname/
+    // * https://datatracker.ietf.org/api/v1/name/ballotpositionname/
+    //   https://datatracker.ietf.org/api/v1/name/reviewrequeststatename/
+    // * https://datatracker.ietf.org/api/v1/name/groupmilestonestatename/
+    //   https://datatracker.ietf.org/api/v1/name/iprlicensetypename/
+    //   https://datatracker.ietf.org/api/v1/name/feedbacktypename/
+    //   https://datatracker.ietf.org/api/v1/name/reviewtypename/
+    //   https://datatracker.ietf.org/api/v1/name/iprdisclosurestatename/
+    //   https://datatracker.ietf.org/api/v1/name/reviewresultname/
+    //   https://datatracker.ietf.org/api/v1/name/liaisonstatementstate/
+    //   https://datatracker.ietf.org/api/v1/name/roomresourcename/
+    //   https://datatracker.ietf.org/api/v1/name/liaisonstatementtagname/
+    //   https://datatracker.ietf.org/api/v1/name/topicaudiencename/
+    //   https://datatracker.ietf.org/api/v1/name/continentname/
+    //   https://datatracker.ietf.org/api/v1/name/nomineepositionstatename/
+    //   https://datatracker.ietf.org/api/v1/name/importantdatename/
+    //   https://datatracker.ietf.org/api/v1/name/liaisonstatementpurposename/
+    //   https://datatracker.ietf.org/api/v1/name/constraintname/
+    //   https://datatracker.ietf.org/api/v1/name/sessionstatusname/
+    //   https://datatracker.ietf.org/api/v1/name/ipreventtypename/
+    //   https://datatracker.ietf.org/api/v1/name/agendatypename/
+    //   https://datatracker.ietf.org/api/v1/name/docremindertypename/
+    //   https://datatracker.ietf.org/api/v1/name/intendedstdlevelname/
+    //   https://datatracker.ietf.org/api/v1/name/countryname/
+    // * https://datatracker.ietf.org/api/v1/name/grouptypename/
+    //   https://datatracker.ietf.org/api/v1/name/draftsubmissionstatename/
+    //   https://datatracker.ietf.org/api/v1/name/rolename/
+
+
+
+    // ----------------------------------------------------------------------------------------------------------------------------
+    // Datatracker API endpoints returning information about working groups:
+    // * https://datatracker.ietf.org/api/v1/group/group/                               - list of groups
+    // * https://datatracker.ietf.org/api/v1/group/group/2161/                          - info about group 2161
+    // * https://datatracker.ietf.org/api/v1/group/grouphistory/?group=2161             - history
+    //   https://datatracker.ietf.org/api/v1/group/groupurl/?group=2161                 - URLs
+    // * https://datatracker.ietf.org/api/v1/group/groupevent/?group=2161               - events
+    // * https://datatracker.ietf.org/api/v1/group/groupmilestone/?group=2161           - Current milestones
+    // * https://datatracker.ietf.org/api/v1/group/groupmilestonehistory/?group=2161    - Previous milestones
+    // * https://datatracker.ietf.org/api/v1/group/milestonegroupevent/?group=2161      - changed milestones
+    // * https://datatracker.ietf.org/api/v1/group/role/?group=2161                     - The current WG chairs and ADs of a group
+    // * https://datatracker.ietf.org/api/v1/group/role/?person=20209                   - Groups a person is currently involved with
+    // * https://datatracker.ietf.org/api/v1/group/role/?email=csp@csperkins.org        - Groups a person is currently involved with
+    // * https://datatracker.ietf.org/api/v1/group/rolehistory/?group=2161              - The previous WG chairs and ADs of a group
+    // * https://datatracker.ietf.org/api/v1/group/rolehistory/?person=20209            - Groups person was previously involved with
+    // * https://datatracker.ietf.org/api/v1/group/rolehistory/?email=csp@csperkins.org - Groups person was previously involved with
+    // * https://datatracker.ietf.org/api/v1/group/changestategroupevent/?group=2161    - Group state changes
+    //   https://datatracker.ietf.org/api/v1/group/groupstatetransitions                - ???
+    // * https://datatracker.ietf.org/api/v1/name/grouptypename/
+    // * https://datatracker.ietf.org/api/v1/name/groupstatename/
+
+    pub fn group(&self, group_uri : &GroupUri) -> DTResult<Group> {
+        self.get(group_uri)
+    }
+
+
+    // The group with the given acronym, e.g., "avtcore"
+    pub fn group_from_acronym(&self, acronym : &str) -> DTResult<Group> {
+        match self.groups().exact("acronym", acronym).fetch()?.next() {
+            Some(group) => group,
+            None        => Err(DatatrackerError::NotFound)
         }
     }
 
 
-    // ----------------------------------------------------------------------------------------------------------------------------
-    // Datatracker API endpoints returning information about email addresses:
-    // * https://datatracker.ietf.org/api/v1/person/email/csp@csperkins.org/
-    // * https://datatracker.ietf.org/api/v1/person/historicalemail/
+    // A query on the list of groups, that can be filtered, for example:
+    //   dt.groups().group_type(&GroupTypeUri::from_slug("wg")).state(&GroupStateUri::from_slug("active")).fetch()
+    pub fn groups(&self) -> Query<'_, Datatracker, Group> {
+        self.query::<Group>()
+    }
+
+
+    // The hierarchy of all groups. There are several thousand groups, so
+    // these are fetched in pages of the largest size the Datatracker allows.
+    pub fn group_tree(&self) -> DTResult<GroupTree> {
+        Ok(GroupTree::new(self.groups().limit(1000).fetch()?.collect::<DTResult<Vec<_>>>()?))
+    }
+
+
+    pub fn group_type(&self, group_type_uri : &GroupTypeUri) -> DTResult<GroupType> {
+        self.get(group_type_uri)
+    }
+
+
+    pub fn group_types<'a>(&'a self) -> DTResult<PaginatedList<'a, GroupType>> {
+        self.query::<GroupType>().fetch()
+    }
+
+
+    pub fn group_state(&self, group_state_uri : &GroupStateUri) -> DTResult<GroupState> {
+        self.get(group_state_uri)
+    }
+
+
+    pub fn group_states<'a>(&'a self) -> DTResult<PaginatedList<'a, GroupState>> {
+        self.query::<GroupState>().fetch()
+    }
+
+
+    pub fn group_history(&self, group_history_uri : &GroupHistoryUri) -> DTResult<GroupHistory> {
+        self.get(group_history_uri)
+    }
+
+
+    // The snapshots of a group, oldest first
+    pub fn group_history_for_group<'a>(&'a self, group : &Group) -> DTResult<PaginatedList<'a, GroupHistory>> {
+        self.query::<GroupHistory>().group(&group.resource_uri).order_by("time").fetch()
+    }
+
+
+    // The group as it was at the given time, or None if the group has no
+    // record from before that time. For example, to find the area that a
+    // working group was in when it was chartered:
+    //   dt.group_at(&group.resource_uri, chartered)?.and_then(|g| g.parent)
+    pub fn group_at(&self, group_uri : &GroupUri, time : DateTime<Utc>) -> DTResult<Option<Group>> {
+        let group = self.group(group_uri)?;
+        if time >= group.time {
+            return Ok(Some(group));
+        }
+        match self.query::<GroupHistory>().group(group_uri).lte("time", time).order_by("-time").limit(1).fetch()?.next() {
+            Some(snapshot) => Ok(Some(snapshot?.into_group(&group))),
+            None           => Ok(None)
+        }
+    }
+
+
+    pub fn group_event(&self, event_uri : &GroupEventUri) -> DTResult<BaseGroupEvent> {
+        self.get(event_uri)
+    }
+
+
+    // A query on the list of group events, that can be filtered, for example:
+    //   dt.group_events().by(&person_uri).since(time).fetch()
+    // Use fetch_typed() to fetch each event as its most specific type.
+    pub fn group_events(&self) -> Query<'_, Datatracker, BaseGroupEvent> {
+        self.query::<BaseGroupEvent>()
+    }
+
+
+    // The history of a group, oldest event first
+    pub fn group_timeline(&self, group : &Group) -> DTResult<Vec<GroupEvent>> {
+        self.group_events().group(&group.resource_uri).order_by("time").fetch_typed()
+    }
+
+
+    pub fn role(&self, role_uri : &RoleUri) -> DTResult<Role> {
+        self.get(role_uri)
+    }
+
+
+    // A query on the list of current roles, that can be filtered, for example:
+    //   dt.roles().name(&RoleName::AreaDirector).fetch()
+    pub fn roles(&self) -> Query<'_, Datatracker, Role> {
+        self.query::<Role>()
+    }
+
+
+    // A query on the list of previous roles, recorded in the history of
+    // each group, that can be filtered in the same way as roles()
+    pub fn role_history(&self) -> Query<'_, Datatracker, RoleHistory> {
+        self.query::<RoleHistory>()
+    }
+
+
+    // The people currently in the given role in a group, e.g., the chairs
+    pub fn group_roles<'a>(&'a self, group : &Group, name : &RoleName) -> DTResult<PaginatedList<'a, Role>> {
+        self.roles().group(&group.resource_uri).name(name).fetch()
+    }
+
+
+    pub fn roles_for_person<'a>(&'a self, person : &Person) -> DTResult<PaginatedList<'a, Role>> {
+        self.roles().person(&person.resource_uri).fetch()
+    }
+
+
+    pub fn role_history_for_person<'a>(&'a self, person : &Person) -> DTResult<PaginatedList<'a, RoleHistory>> {
+        self.role_history().person(&person.resource_uri).fetch()
+    }
+
+
+    // The people who were in the given role in a group at the given time,
+    // e.g., who chaired a working group on a particular date. This uses the
+    // snapshot of the group that was current at that time.
+    pub fn group_role_holders_at(&self, group : &Group, name : &RoleName, time : DateTime<Utc>) -> DTResult<Vec<PersonUri>> {
+        let mut people = if time >= group.time {
+            self.group_roles(group, name)?.map(|role| role.map(|r| r.person)).collect::<DTResult<Vec<_>>>()?
+        } else {
+            let snapshot = self.query::<GroupHistory>().group(&group.resource_uri).lte("time", time).order_by("-time").limit(1).fetch()?.next();
+            match snapshot {
+                Some(snapshot) => self.role_history().group(&snapshot?.resource_uri).name(name).fetch()?
+                                      .map(|role| role.map(|r| r.person))
+                                      .collect::<DTResult<Vec<_>>>()?,
+                None => Vec::new()
+            }
+        };
+        people.sort();
+        people.dedup();
+        Ok(people)
+    }
+
+
+    pub fn group_milestone(&self, milestone_uri : &GroupMilestoneUri) -> DTResult<GroupMilestone> {
+        self.get(milestone_uri)
+    }
+
+
+    // A query on the list of milestones, that can be filtered, for example:
+    //   dt.group_milestones().state(&GroupMilestoneState::Active).due_before(date).fetch()
+    pub fn group_milestones(&self) -> Query<'_, Datatracker, GroupMilestone> {
+        self.query::<GroupMilestone>()
+    }
+
+
+    // The milestones of a group, in order of their due date
+    pub fn milestones_for_group<'a>(&'a self, group : &Group) -> DTResult<PaginatedList<'a, GroupMilestone>> {
+        self.group_milestones().group(&group.resource_uri).order_by("due").fetch()
+    }
+
+
+    // The active milestones of a group that are unresolved and were due
+    // before the given date, e.g., Utc::now().date_naive()
+    pub fn overdue_milestones(&self, group : &Group, date : NaiveDate) -> DTResult<Vec<GroupMilestone>> {
+        let mut overdue = Vec::new();
+        for milestone in self.group_milestones().group(&group.resource_uri).state(&GroupMilestoneState::Active).due_before(date).order_by("due").fetch()? {
+            let milestone = milestone?;
+            if milestone.is_overdue(date) {
+                overdue.push(milestone);
+            }
+        }
+        Ok(overdue)
+    }
+
+
+    // The previous versions of a milestone, oldest first
+    pub fn milestone_history<'a>(&'a self, milestone : &GroupMilestone) -> DTResult<PaginatedList<'a, GroupMilestoneHistory>> {
+        self.query::<GroupMilestoneHistory>().exact("milestone", milestone.id).order_by("time").fetch()
+    }
+
+
+    // The due dates of a milestone, oldest first, with the time that each
+    // was set, showing how the milestone slipped
+    pub fn milestone_due_dates(&self, milestone : &GroupMilestone) -> DTResult<Vec<(DateTime<Utc>, NaiveDate)>> {
+        let history = self.milestone_history(milestone)?.collect::<DTResult<Vec<_>>>()?;
+        Ok(milestone.due_dates(&history))
+    }
+
+
+    // The changes to the milestones of a group, oldest first
+    pub fn milestone_events<'a>(&'a self, group : &Group) -> DTResult<PaginatedList<'a, MilestoneGroupEvent>> {
+        self.query::<MilestoneGroupEvent>().group(&group.resource_uri).order_by("time").fetch()
+    }
+
+
+    pub fn group_milestone_state_names<'a>(&'a self) -> DTResult<PaginatedList<'a, GroupMilestoneStateName>> {
+        self.query::<GroupMilestoneStateName>().fetch()
+    }
+
+
+    // ----------------------------------------------------------------------------------------------------------------------------
+    // Datatracker API endpoints returning information about meetings:
+    //   https://datatracker.ietf.org/api/v1/meeting/meeting/                        - list of meetings
+    //   https://datatracker.ietf.org/api/v1/meeting/meeting/747/                    - information about meeting number 747
+    //   https://datatracker.ietf.org/api/v1/meeting/session/                        - list of all sessions in meetings
+    //   https://datatracker.ietf.org/api/v1/meeting/session/25886/                  - a session in a meeting
+    //   https://datatracker.ietf.org/api/v1/meeting/session/?meeting=747            - sessions in meeting number 747
+    //   https://datatracker.ietf.org/api/v1/meeting/session/?meeting=747&group=2161 - sessions in meeting number 747 for group 2161
+    //   https://datatracker.ietf.org/api/v1/meeting/schedtimesessassignment/59003/  - a schededuled session within a meeting
+    //   https://datatracker.ietf.org/api/v1/meeting/timeslot/9480/                  - a time slot within a meeting (time, duration, location)
+    //   https://datatracker.ietf.org/api/v1/meeting/schedule/791/                   - a draft of the meeting agenda
+    //   https://datatracker.ietf.org/api/v1/meeting/room/537/                       - a room at a meeting
+    //   https://datatracker.ietf.org/api/v1/meeting/floorplan/14/                   - floor plan for a meeting venue
+    //   https://datatracker.ietf.org/api/v1/name/meetingtypename/
+
+
+
+}
+
+
+impl Default for Datatracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// =================================================================================================================================
+// Test suite:
+
+#[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
+mod ietfdata_tests {
+    use super::*;
+
+    use std::env;
+    use std::fs;
+    use std::path::PathBuf;
+
+    use futures::{Stream, TryStreamExt};
+
+    // The tests run offline, replaying fixtures written by hand in the format saved by
+    // DatatrackerBuilder::record_fixtures(). These are not responses recorded from the
+    // Datatracker, and many of the assertions hold only for this data.
+    fn synthetic_builder(dir : &str) -> DatatrackerBuilder {
+        let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests").join("fixtures").join(dir);
+        Datatracker::builder().base_url("http://invalid.example").replay_fixtures(&dir)
+    }
+
+    // A small set of people, documents, and groups, related as on the Datatracker
+    fn synthetic_datatracker() -> Datatracker {
+        synthetic_builder("synthetic").build().expect("Cannot initialise Datatracker connection")
+    }
+
+    fn synthetic_async_datatracker() -> AsyncDatatracker {
+        synthetic_builder("synthetic").build_async().expect("Cannot initialise Datatracker connection")
+    }
+
+    // Two document state types, listed in short pages, and a small state machine
+    fn states_datatracker() -> Datatracker {
+        synthetic_builder("synthetic/states").build().expect("Cannot initialise Datatracker connection")
+    }
+
+    // ----------------------------------------------------------------------------------------------------------------------------
+    // Tests relating to the connection:
+
+    #[test]
+    fn test_builder() -> DTResult<()> {
+        let dt = Datatracker::new();
+        assert_eq!(dt.base_url(), "https://datatracker.ietf.org");
+
+        let dt = Datatracker::builder()
+                    .base_url("http://localhost:8000/")
+                    .user_agent("ietfdata-rs-test")
+                    .timeout(Duration::from_secs(10))
+                    .connect_timeout(Duration::from_secs(5))
+                    .build()?;
+        assert_eq!(dt.base_url(), "http://localhost:8000");
+
+        let dt = Datatracker::builder()
+                    .base_url("https://datatracker.staging.ietf.org")
+                    .client(reqwest::blocking::Client::new())
+                    .build()?;
+        assert_eq!(dt.base_url(), "https://datatracker.staging.ietf.org");
+
+        match Datatracker::builder().user_agent("bad\nagent").build() {
+            Err(DatatrackerError::InvalidUserAgent(_)) => {}
+            _ => panic!("expected InvalidUserAgent")
+        }
+
+        for rate in &[0.0, -1.0, f64::NAN, 1e-300, 1e-19, 1.0 / (2.0 * 24.0 * 60.0 * 60.0)] {
+            match Datatracker::builder().rate_limit(*rate).build() {
+                Err(DatatrackerError::InvalidRateLimit(_)) => {}
+                _ => panic!("expected InvalidRateLimit")
+            }
+        }
+        assert!(matches!(Datatracker::builder().rate_limit(0.0).build_async(), Err(DatatrackerError::InvalidRateLimit(_))));
+
+        Ok(())
+    }
+
+
+    #[test]
+    fn test_replay_fixtures() -> DTResult<()> {
+        let dt = states_datatracker();
+
+        let st = dt.doc_state_type(&DocStateTypeUri("/api/v1/doc/statetype/draft/".to_string()))?;
+        assert_eq!(st.slug,  "draft");
+        assert_eq!(st.label, "State");
+
+        let st = dt.doc_state_types()?.collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(st.len(), 2);
+        assert_eq!(st[0].slug, "draft");
+        assert_eq!(st[1].slug, "draft-iesg");
+
+        assert!(dt.doc_state_type(&DocStateTypeUri("/api/v1/doc/statetype/none/".to_string())).is_err());
+        assert!(dt.doc_state_type(&DocStateTypeUri("/api/v1/doc/statetype/missing/".to_string())).is_err());
+
+        Ok(())
+    }
+
+
+    #[test]
+    fn test_pagination() -> DTResult<()> {
+        let dt = states_datatracker();
+
+        let mut list = dt.doc_state_types()?;
+        assert_eq!(list.total_count(), 2);
+        assert_eq!(list.limit(),       1);
+        assert_eq!(list.offset(),      0);
+        assert_eq!(list.size_hint(),   (2, None));
+        assert_eq!(list.next().unwrap()?.slug, "draft");
+        assert_eq!(list.offset(),      1);
+        assert_eq!(list.size_hint(),   (1, None));
+        assert_eq!(list.next().unwrap()?.slug, "draft-iesg");
+        assert_eq!(list.offset(),      2);
+        assert_eq!(list.size_hint(),   (0, None));
+        assert!(list.next().is_none());
+
+        // Resume from a checkpoint
+        let mut list = dt.query::<DocStateType>().order_by("slug").offset(1).fetch()?;
+        assert_eq!(list.offset(),      1);
+        assert_eq!(list.next().unwrap()?.slug, "draft-iesg");
+        assert!(list.next().is_none());
 
-    pub fn email(&self, email_uri: &EmailUri) -> DTResult<Email> {
-        let url = format!("https://datatracker.ietf.org{}", email_uri.0);
-        self.retrieve::<Email>(&url)
+        Ok(())
     }
 
-    pub fn email_from_address(&self, email_addr : &str) -> DTResult<Email> {
-        let url = format!("https://datatracker.ietf.org/api/v1/person/email/{}/", email_addr);
-        self.retrieve::<Email>(&url)
-    }
 
+    #[test]
+    fn test_get() -> DTResult<()> {
+        let dt = synthetic_datatracker();
 
-    pub fn email_history_for_address<'a>(&'a self, email_addr : &'a str) -> DTResult<PaginatedList<HistoricalEmail>> {
-        let url = format!("https://datatracker.ietf.org/api/v1/person/historicalemail/?address={}", email_addr);
-        PaginatedList::<'a, HistoricalEmail>::new(&self.connection, url)
-    }
+        // The type of the result follows from the type of the URI
+        let st = dt.get(&DocStateUri("/api/v1/doc/state/1/".to_string()))?;
+        assert_eq!(st.slug, "active");
+        let st = dt.get(&st.state_type)?;
+        assert_eq!(st.label, "State");
 
+        assert_eq!(<DocState as Endpoint>::PATH, "/api/v1/doc/state/");
+        assert_eq!(st.resource_uri.as_str(), "/api/v1/doc/statetype/draft/");
 
-    pub fn email_history_for_person<'a>(&'a self, person : &'a Person) -> DTResult<PaginatedList<HistoricalEmail>> {
-        let url = format!("https://datatracker.ietf.org/api/v1/person/historicalemail/?person={}", person.id);
-        PaginatedList::<'a, HistoricalEmail>::new(&self.connection, url)
+        Ok(())
     }
 
 
-    // ----------------------------------------------------------------------------------------------------------------------------
-    // Datatracker API endpoints returning information about people:
-    // * https://datatracker.ietf.org/api/v1/person/person/20209/
-    // * https://datatracker.ietf.org/api/v1/person/person/
-    // * https://datatracker.ietf.org/api/v1/person/historicalperson/
-    // * https://datatracker.ietf.org/api/v1/person/alias/
-
-    pub fn person(&self, person_uri : &PersonUri) -> DTResult<Person> {
-        let url = format!("https://datatracker.ietf.org{}", person_uri.0);
-        self.retrieve::<Person>(&url)
+    #[test]
+    fn test_uri() -> DTResult<()> {
+        let uri : PersonUri = "/api/v1/person/person/20209/".parse()?;
+        assert_eq!(uri,                 PersonUri::from_id(20209));
+        assert_eq!(uri.id(),            Some(20209));
+        assert_eq!(uri.to_string(),     "/api/v1/person/person/20209/");
+
+        let uri : DocStateTypeUri = "/api/v1/doc/statetype/draft/".parse()?;
+        assert_eq!(uri,                 DocStateTypeUri::from_slug("draft"));
+        assert_eq!(uri.slug(),          Some("draft"));
+
+        let uri = EmailUri::from_address("csp@csperkins.org");
+        assert_eq!(uri.as_str(),        "/api/v1/person/email/csp@csperkins.org/");
+        assert_eq!(uri.address(),       Some("csp@csperkins.org"));
+
+        assert!("/api/v1/person/person/csp/".parse::<PersonUri>().is_err());
+        assert!("/api/v1/person/person/20209".parse::<PersonUri>().is_err());
+        assert!("/api/v1/person/person/".parse::<PersonUri>().is_err());
+        assert!("/api/v1/doc/state/81/".parse::<PersonUri>().is_err());
+        assert!("/api/v1/doc/statetype/draft/x/".parse::<DocStateTypeUri>().is_err());
+        assert_eq!(PersonUri("/api/v1/doc/state/81/".to_string()).id(), None);
+
+        // Identifiers that are not valid in a path segment are percent-encoded
+        let uri = DocumentUri::from_name("../rfc/3550");
+        assert_eq!(uri.as_str(),        "/api/v1/doc/document/..%2Frfc%2F3550/");
+        assert_eq!(uri.name(),          Some("..%2Frfc%2F3550"));
+        assert_eq!(uri.to_string().parse::<DocumentUri>()?, uri);
+
+        let uri : ContentTypeUri = "/api/v1/contenttypes/contenttype/29/".parse()?;
+        assert_eq!(uri.id(),            Some(29));
+        assert_eq!(uri.to_string(),     "/api/v1/contenttypes/contenttype/29/");
+        assert_eq!(MessageUri::from_id(5).to_string(),                  "/api/v1/message/message/5/");
+        assert_eq!(ReviewRequestUri::from_id(7).id(),                   Some(7));
+        assert_eq!(ReviewRequestStateUri::from_slug("assigned").slug(), Some("assigned"));
+        assert!("/api/v1/message/message/x/".parse::<MessageUri>().is_err());
+        assert!("/api/v1/review/reviewrequest/".parse::<ReviewRequestUri>().is_err());
+        assert!("/api/v1/doc/state/1/".parse::<ReviewRequestStateUri>().is_err());
+
+        // URIs can be used as map keys
+        let mut names = std::collections::BTreeMap::new();
+        names.insert(PersonUri::from_id(20209), "Colin Perkins");
+        assert_eq!(names.get(&"/api/v1/person/person/20209/".parse()?), Some(&"Colin Perkins"));
+        Ok(())
     }
 
 
-    pub fn person_from_email(&self, email : &EmailUri) -> DTResult<Person> {
-        let person = self.email(email)?.person;
-        self.person(&person)
-    }
+    #[test]
+    fn test_cache_only() -> DTResult<()> {
+        let dir   = tempfile::tempdir().unwrap();
+        let cache = Cache::new(dir.path())
+                        .default_ttl(Duration::from_secs(60))
+                        .ttl("/api/v1/doc/", Duration::from_secs(3600))
+                        .ttl("/api/v1/doc/statetype/", Duration::from_secs(86400))
+                        .cache_only(true);
+        assert_eq!(cache.ttl_for("/api/v1/person/person/20209/"), Duration::from_secs(60));
+        assert_eq!(cache.ttl_for("/api/v1/doc/state/81/"),        Duration::from_secs(3600));
+        assert_eq!(cache.ttl_for("/api/v1/doc/statetype/draft/"), Duration::from_secs(86400));
+
+        let body = r#"{"resource_uri": "/api/v1/doc/statetype/draft/", "slug": "draft", "label": "State"}"#;
+        cache.update("http://invalid.example/api/v1/doc/statetype/draft/", None, 200, &reqwest::header::HeaderMap::new(), body.to_string())?;
+
+        let dt = Datatracker::builder().base_url("http://invalid.example").cache(cache).build()?;
+        let st = dt.doc_state_type(&DocStateTypeUri("/api/v1/doc/statetype/draft/".to_string()))?;
+        assert_eq!(st.slug,  "draft");
+        assert_eq!(st.label, "State");
 
-    pub fn person_from_email_address(&self, email_addr : &str) -> DTResult<Person> {
-        let person = self.email_from_address(email_addr)?.person;
-        self.person(&person)
+        match dt.doc_state_type(&DocStateTypeUri("/api/v1/doc/statetype/none/".to_string())) {
+            Err(DatatrackerError::NotInCache(url)) => assert_eq!(url, "http://invalid.example/api/v1/doc/statetype/none/"),
+            _ => panic!("expected NotInCache")
+        }
+
+        // Responses are cached separately for each Datatracker instance
+        let cache = Cache::new(dir.path()).cache_only(true);
+        let dt    = Datatracker::builder().base_url("https://invalid.example").cache(cache).build()?;
+        assert!(matches!(dt.doc_state_type(&DocStateTypeUri::from_slug("draft")), Err(DatatrackerError::NotInCache(_))));
+
+        // Long URLs are shortened to a valid filename
+        let cache = dt.cache().unwrap();
+        let long  = format!("http://invalid.example/api/v1/doc/document/?name__in={}", vec!["draft-ietf-avtcore-rtp-circuit-breakers"; 20].join(","));
+        let name  = cache.path_for(&long).file_name().unwrap().to_string_lossy().into_owned();
+        assert!(name.len() <= 200);
+        assert!(name.starts_with("doc_2Fdocument_2F_3Fname_5F_5Fin_3Ddraft-ietf-avtcore"));
+        assert_ne!(cache.path_for(&long), cache.path_for(&format!("{},rfc3550", long)));
+        Ok(())
     }
 
 
-    pub fn person_aliases<'a>(&'a self, person : &'a Person) -> DTResult<PaginatedList<PersonAlias>> {
-        let url = format!("https://datatracker.ietf.org/api/v1/person/alias/?person={}", person.id);
-        PaginatedList::<'a, PersonAlias>::new(&self.connection, url)
+    #[test]
+    fn test_cache_revalidation() -> DTResult<()> {
+        let dir  = tempfile::tempdir().unwrap();
+        let body = r#"{"resource_uri": "/api/v1/doc/statetype/draft/", "slug": "draft", "label": "State"}"#;
+        let uri  = DocStateTypeUri::from_slug("draft");
+        let base = serve_responses(vec!((200, "ETag: \"v1\"\r\n", body), (304, "", "")));
+        let dt   = Datatracker::builder()
+                      .base_url(&base)
+                      .retry(RetryPolicy::none())
+                      .cache(Cache::new(dir.path()).default_ttl(Duration::from_secs(3600)))
+                      .build()?;
+        let url  = format!("{}/api/v1/doc/statetype/draft/", base);
+
+        assert_eq!(dt.doc_state_type(&uri)?.slug, "draft");
+        let mut entry = dt.cache().unwrap().load(&url)?.unwrap();
+        assert_eq!(entry.etag, Some("\"v1\"".to_string()));
+
+        // Make the entry stale, so it is revalidated. The server replies 304 Not Modified.
+        entry.fetched = 0;
+        fs::write(dt.cache().unwrap().path_for(&url), serde_json::to_string(&entry).unwrap()).unwrap();
+        assert_eq!(dt.doc_state_type(&uri)?.slug, "draft");
+
+        // The time-to-live was refreshed, so the server is not contacted again
+        let entry = dt.cache().unwrap().load(&url)?.unwrap();
+        assert!(entry.fetched > Utc::now().timestamp() - 60);
+        assert_eq!(entry.body, body);
+        assert_eq!(dt.doc_state_type(&uri)?.label, "State");
+        Ok(())
     }
 
 
-    pub fn person_history<'a>(&'a self, person : &'a Person) -> DTResult<PaginatedList<HistoricalPerson>> {
-        let url = format!("https://datatracker.ietf.org/api/v1/person/historicalperson/?id={}", person.id);
-        PaginatedList::<'a, HistoricalPerson>::new(&self.connection, url)
+    // A minimal HTTP server that sends each of the given responses (status, extra headers,
+    // body) in turn, one per connection
+    fn serve_responses(responses : Vec<(u16, &'static str, &'static str)>) -> String {
+        use std::io::{BufRead, BufReader, Write};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        thread::spawn(move || {
+            for (status, headers, body) in responses {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut line = String::new();
+                while reader.read_line(&mut line).unwrap() > 2 {
+                    line.clear();
+                }
+                let response = format!("HTTP/1.1 {} X\r\nContent-Length: {}\r\nConnection: close\r\n{}\r\n{}",
+                                       status, body.len(), headers, body);
+                stream.write_all(response.as_bytes()).unwrap();
+            }
+        });
+        base_url
     }
 
 
-    // FIXME: builder pattern for this, and similar functions
-    pub fn people<'a>(&'a self) -> DTResult<PaginatedList<'a, Person>> {
-        let url = format!("https://datatracker.ietf.org/api/v1/person/person/");
-        PaginatedList::<'a, Person>::new(&self.connection, url)
-    }
+    #[test]
+    fn test_retry() -> DTResult<()> {
+        let body = r#"{"resource_uri": "/api/v1/doc/statetype/draft/", "slug": "draft", "label": "State"}"#;
+        let uri  = DocStateTypeUri("/api/v1/doc/statetype/draft/".to_string());
+
+        let base = serve_responses(vec!((503, "", ""), (429, "Retry-After: 0\r\n", ""), (200, "", body)));
+        let dt   = Datatracker::builder()
+                      .base_url(&base)
+                      .retry(RetryPolicy::new().initial_backoff(Duration::from_millis(1)))
+                      .rate_limit(100.0)
+                      .build()?;
+        assert_eq!(dt.doc_state_type(&uri)?.slug, "draft");
+
+        let base = serve_responses(vec!((503, "", "")));
+        let dt   = Datatracker::builder().base_url(&base).retry(RetryPolicy::none()).build()?;
+        match dt.doc_state_type(&uri) {
+            Err(DatatrackerError::HttpError{url, status}) => {
+                assert_eq!(url, format!("{}/api/v1/doc/statetype/draft/", base));
+                assert_eq!(status, 503);
+            }
+            _ => panic!("expected HttpError")
+        }
 
+        let base = serve_responses(vec!((429, "Retry-After: 30\r\n", "")));
+        let dt   = Datatracker::builder().base_url(&base).retry(RetryPolicy::none()).build()?;
+        match dt.doc_state_type(&uri) {
+            Err(DatatrackerError::RateLimited{retry_after, ..}) => assert_eq!(retry_after, Some(Duration::from_secs(30))),
+            _ => panic!("expected RateLimited")
+        }
 
-    pub fn people_with_name<'a>(&'a self, name: &'a str) -> DTResult<PaginatedList<'a, Person>> {
-        let url = format!("https://datatracker.ietf.org/api/v1/person/person/?name={}", name);
-        PaginatedList::<'a, Person>::new(&self.connection, url)
+        // A Retry-After longer than the maximum backoff is not waited for
+        let base = serve_responses(vec!((429, "Retry-After: 3600\r\n", "")));
+        let dt   = Datatracker::builder().base_url(&base).retry(RetryPolicy::new().max_backoff(Duration::from_secs(5))).build()?;
+        match dt.doc_state_type(&uri) {
+            Err(DatatrackerError::RateLimited{retry_after, ..}) => assert_eq!(retry_after, Some(Duration::from_secs(3600))),
+            _ => panic!("expected RateLimited")
+        }
+
+        Ok(())
     }
 
 
-    pub fn people_with_name_containing<'a>(&'a self, name_contains: &'a str) -> DTResult<PaginatedList<'a, Person>> {
-        let url = format!("https://datatracker.ietf.org/api/v1/person/person/?name__contains={}", name_contains);
-        PaginatedList::<'a, Person>::new(&self.connection, url)
+    #[test]
+    fn test_errors() -> DTResult<()> {
+        let uri  = DocStateTypeUri("/api/v1/doc/statetype/draft/".to_string());
+        let body = r#"{"resource_uri": "/api/v1/doc/statetype/draft/", "slug": 42, "label": "State"}"#;
+        let base = serve_responses(vec!((404, "", ""), (200, "", body)));
+        let dt   = Datatracker::builder().base_url(&base).retry(RetryPolicy::none()).build()?;
+
+        match dt.doc_state_type(&uri) {
+            Err(DatatrackerError::NotFound) => {}
+            _ => panic!("expected NotFound")
+        }
+        match dt.doc_state_type(&uri) {
+            Err(DatatrackerError::DeserializationError{path, snippet, ..}) => {
+                assert_eq!(path, "slug");
+                assert!(snippet.contains("\"slug\": 42"));
+            }
+            _ => panic!("expected DeserializationError")
+        }
+        match dt.doc_state_type(&DocStateTypeUri("draft".to_string())) {
+            Err(DatatrackerError::InvalidUri(uri)) => assert_eq!(uri, "draft"),
+            _ => panic!("expected InvalidUri")
+        }
+
+        Ok(())
     }
 
 
+    #[test]
+    fn test_retry_policy() {
+        let policy = RetryPolicy::new().initial_backoff(Duration::from_secs(1)).max_backoff(Duration::from_secs(5)).jitter(false);
+        assert_eq!(policy.backoff(0), Duration::from_secs(1));
+        assert_eq!(policy.backoff(1), Duration::from_secs(2));
+        assert_eq!(policy.backoff(2), Duration::from_secs(4));
+        assert_eq!(policy.backoff(3), Duration::from_secs(5));
+        assert_eq!(policy.backoff(40), Duration::from_secs(5));
+        assert_eq!(policy.delay(0, Some(Duration::from_secs(3))),  Some(Duration::from_secs(3)));
+        assert_eq!(policy.delay(0, Some(Duration::from_secs(10))), None);
+        assert_eq!(policy.delay(1, None),                          Some(Duration::from_secs(2)));
+
+        let policy = RetryPolicy::new().initial_backoff(Duration::from_secs(4));
+        assert!(policy.backoff(0) >= Duration::from_secs(2));
+        assert!(policy.backoff(0) <= Duration::from_secs(4));
+
+        let mut headers = reqwest::header::HeaderMap::new();
+        assert_eq!(retry_after(&headers), None);
+        headers.insert(reqwest::header::RETRY_AFTER, "120".parse().unwrap());
+        assert_eq!(retry_after(&headers), Some(Duration::from_secs(120)));
+        headers.insert(reqwest::header::RETRY_AFTER, "Wed, 21 Oct 2015 07:28:00 GMT".parse().unwrap());
+        assert_eq!(retry_after(&headers), Some(Duration::from_secs(0)));
+
+        let limiter = RateLimiter::new(10.0).unwrap();
+        assert_eq!(limiter.interval(), Duration::from_millis(100));
+        assert_eq!(limiter.reserve(), Duration::from_secs(0));
+        assert!(limiter.reserve() > Duration::from_millis(90));
+        assert!(limiter.reserve() > Duration::from_millis(190));
+
+        let limiter = RateLimiter::new(1.0 / (12.0 * 60.0 * 60.0)).unwrap();
+        assert_eq!(limiter.reserve(), Duration::from_secs(0));
+        assert!(limiter.reserve() > Duration::from_secs(11 * 60 * 60));
+    }
 
-    // ----------------------------------------------------------------------------------------------------------------------------
-    // Datatracker API endpoints returning information about documents:
-    //   https://datatracker.ietf.org/api/v1/doc/document/                        - list of documents
-    //   https://datatracker.ietf.org/api/v1/doc/document/draft-ietf-avt-rtp-new/ - info about document
-    //   https://datatracker.ietf.org/api/v1/doc/docalias/?name=/                 - draft that became the given RFC
-    // * https://datatracker.ietf.org/api/v1/doc/state/                           - Types of state a document can be in
-    // * https://datatracker.ietf.org/api/v1/doc/statetype/                       - Possible types of state for a document
-    //   https://datatracker.ietf.org/api/v1/doc/docevent/                        - list of document events
-    //   https://datatracker.ietf.org/api/v1/doc/docevent/?doc=...                - events for a document
-    //   https://datatracker.ietf.org/api/v1/doc/docevent/?by=...                 - events by a person (as /api/v1/person/person)
-    //   https://datatracker.ietf.org/api/v1/doc/docevent/?time=...               - events by time
-    //   https://datatracker.ietf.org/api/v1/doc/documentauthor/?document=...     - authors of a document
-    //   https://datatracker.ietf.org/api/v1/doc/documentauthor/?person=...       - documents by person (as /api/v1/person/person)
-    //   https://datatracker.ietf.org/api/v1/doc/documentauthor/?email=...        - documents by person with particular email
-    //   https://datatracker.ietf.org/api/v1/doc/dochistory/
-    //   https://datatracker.ietf.org/api/v1/doc/dochistoryauthor/
-    //   https://datatracker.ietf.org/api/v1/doc/docreminder/
-    //   https://datatracker.ietf.org/api/v1/doc/documenturl/
-    //   https://datatracker.ietf.org/api/v1/doc/statedocevent/                   - subset of /api/v1/doc/docevent/; same parameters
-    //   https://datatracker.ietf.org/api/v1/doc/ballotdocevent/                  -               "                "
-    //   https://datatracker.ietf.org/api/v1/doc/newrevisiondocevent/             -               "                "
-    //   https://datatracker.ietf.org/api/v1/doc/submissiondocevent/              -               "                "
-    //   https://datatracker.ietf.org/api/v1/doc/writeupdocevent/                 -               "                "
-    //   https://datatracker.ietf.org/api/v1/doc/consensusdocevent/               -               "                "
-    //   https://datatracker.ietf.org/api/v1/doc/ballotpositiondocevent/          -               "                "
-    //   https://datatracker.ietf.org/api/v1/doc/reviewrequestdocevent/           -               "                "
-    //   https://datatracker.ietf.org/api/v1/doc/lastcalldocevent/                -               "                "
-    //   https://datatracker.ietf.org/api/v1/doc/telechatdocevent/                -               "                "
-    //   https://datatracker.ietf.org/api/v1/doc/relateddocument/?source=...      - documents that source draft relates to (references, replaces, etc)
-    //   https://datatracker.ietf.org/api/v1/doc/relateddocument/?target=...      - documents that relate to target draft
-    //   https://datatracker.ietf.org/api/v1/doc/ballottype/                      - Types of ballot that can be issued on a document
-    //   https://datatracker.ietf.org/api/v1/doc/relateddochistory/
-    //   https://datatracker.ietf.org/api/v1/doc/initialreviewdocevent/
-    //   https://datatracker.ietf.org/api/v1/doc/deletedevent/
-    //   https://datatracker.ietf.org/api/v1/doc/addedmessageevent/
-    //   https://datatracker.ietf.org/api/v1/doc/editedauthorsdocevent/
 
-    pub fn doc_state(&self, state_uri: &DocStateUri) -> DTResult<DocState> {
-        let url = format!("https://datatracker.ietf.org{}", state_uri.0);
-        println!("{:?}", url);
-        self.retrieve::<DocState>(&url)
-    }
+    #[tokio::test]
+    async fn test_async_replay_fixtures() -> DTResult<()> {
+        let dt = synthetic_builder("synthetic/states").build_async()?;
 
+        let st = dt.doc_state_type(&DocStateTypeUri("/api/v1/doc/statetype/draft/".to_string())).await?;
+        assert_eq!(st.slug,  "draft");
+        assert_eq!(st.label, "State");
 
-    pub fn doc_states<'a>(&'a self) -> DTResult<PaginatedList<'a, DocState>> {
-        let url = format!("https://datatracker.ietf.org/api/v1/doc/state/");
-        PaginatedList::<'a, DocState>::new(&self.connection, url)
-    }
+        let list = dt.doc_state_types().await?;
+        assert_eq!(list.total_count(), 2);
+        assert_eq!(list.size_hint(),   (2, None));
+        let st = list.try_collect::<Vec<_>>().await?;
+        assert_eq!(st.len(), 2);
+        assert_eq!(st[0].slug, "draft");
+        assert_eq!(st[1].slug, "draft-iesg");
 
+        assert!(dt.doc_state_type(&DocStateTypeUri("/api/v1/doc/statetype/none/".to_string())).await.is_err());
 
-    pub fn doc_state_type(&self, state_type_uri: &DocStateTypeUri) -> DTResult<DocStateType> {
-        let url = format!("https://datatracker.ietf.org{}", state_type_uri.0);
-        self.retrieve::<DocStateType>(&url)
+        Ok(())
     }
 
 
-    pub fn doc_state_types<'a>(&'a self) -> DTResult<PaginatedList<'a, DocStateType>> {
-        let url = format!("https://datatracker.ietf.org/api/v1/doc/statetype/");
-        PaginatedList::<'a, DocStateType>::new(&self.connection, url)
+    #[tokio::test]
+    async fn test_async_pagination_error() -> DTResult<()> {
+        let dir      = tempfile::tempdir().unwrap();
+        let fixtures = Fixtures::new(FixtureMode::Record, dir.path());
+        fixtures.save("/api/v1/doc/statetype/", 200,
+                      r#"{"meta": {"total_count": 2, "limit": 1, "offset": 0, "previous": null, "next": "/api/v1/doc/statetype/?limit=1&offset=1"},
+                          "objects": [{"resource_uri": "/api/v1/doc/statetype/draft/", "slug": "draft", "label": "State"}]}"#)?;
+        let dt = Datatracker::builder().base_url("http://invalid.example").replay_fixtures(dir.path()).build_async()?;
+
+        // A failure to fetch a page doesn't end the stream; the page is fetched again when next polled
+        let mut list = dt.doc_state_types().await?;
+        assert_eq!(list.try_next().await?.unwrap().slug, "draft");
+        assert!(matches!(list.try_next().await, Err(DatatrackerError::FixtureError(..))));
+        fixtures.save("/api/v1/doc/statetype/?limit=1&offset=1", 200,
+                      r#"{"meta": {"total_count": 2, "limit": 1, "offset": 1, "previous": "/api/v1/doc/statetype/?limit=1&offset=0", "next": null},
+                          "objects": [{"resource_uri": "/api/v1/doc/statetype/draft-iesg/", "slug": "draft-iesg", "label": "IESG state"}]}"#)?;
+        assert_eq!(list.try_next().await?.unwrap().slug, "draft-iesg");
+        assert!(list.try_next().await?.is_none());
+        Ok(())
     }
 
 
-    // ----------------------------------------------------------------------------------------------------------------------------
-    // Datatracker API endpoints returning information about names:
-    //   https://datatracker.ietf.org/api/v1/name/doctypename/
-    //   https://datatracker.ietf.org/api/v1/name/streamname/
-    //   https://datatracker.ietf.org/api/v1/name/dbtemplatetypename/
-    //   https://datatracker.ietf.org/api/v1/name/docrelationshipname/
-    //   https://datatracker.ietf.org/api/v1/name/doctagname/
-    //   https://datatracker.ietf.org/api/v1/name/docurltagname/
-    //   https://datatracker.ietf.org/api/v1/name/groupstatename/
-    //   https://datatracker.ietf.org/api/v1/name/formallanguagename/
-    //   https://datatracker.ietf.org/api/v1/name/timeslottypename/
-    //   https://datatracker.ietf.org/api/v1/name/liaisonstatementeventtypename/
-    //   https://datatracker.ietf.org/api/v1/name/stdlevelname/
-    //   https://datatracker.ietf.org/api/v1/name/ballotpositionname/
-    //   https://datatracker.ietf.org/api/v1/name/reviewrequeststatename/
-    //   https://datatracker.ietf.org/api/v1/name/groupmilestonestatename/
-    //   https://datatracker.ietf.org/api/v1/name/iprlicensetypename/
-    //   https://datatracker.ietf.org/api/v1/name/feedbacktypename/
-    //   https://datatracker.ietf.org/api/v1/name/reviewtypename/
-    //   https://datatracker.ietf.org/api/v1/name/iprdisclosurestatename/
-    //   https://datatracker.ietf.org/api/v1/name/reviewresultname/
-    //   https://datatracker.ietf.org/api/v1/name/liaisonstatementstate/
-    //   https://datatracker.ietf.org/api/v1/name/roomresourcename/
-    //   https://datatracker.ietf.org/api/v1/name/liaisonstatementtagname/
-    //   https://datatracker.ietf.org/api/v1/name/topicaudiencename/
-    //   https://datatracker.ietf.org/api/v1/name/continentname/
-    //   https://datatracker.ietf.org/api/v1/name/nomineepositionstatename/
-    //   https://datatracker.ietf.org/api/v1/name/importantdatename/
-    //   https://datatracker.ietf.org/api/v1/name/liaisonstatementpurposename/
-    //   https://datatracker.ietf.org/api/v1/name/constraintname/
-    //   https://datatracker.ietf.org/api/v1/name/sessionstatusname/
-    //   https://datatracker.ietf.org/api/v1/name/ipreventtypename/
-    //   https://datatracker.ietf.org/api/v1/name/agendatypename/
-    //   https://datatracker.ietf.org/api/v1/name/docremindertypename/
-    //   https://datatracker.ietf.org/api/v1/name/intendedstdlevelname/
-    //   https://datatracker.ietf.org/api/v1/name/countryname/
-    //   https://datatracker.ietf.org/api/v1/name/grouptypename/
-    //   https://datatracker.ietf.org/api/v1/name/draftsubmissionstatename/
-    //   https://datatracker.ietf.org/api/v1/name/rolename/
+    #[tokio::test]
+    async fn test_async_person() -> DTResult<()> {
+        let dt = synthetic_async_datatracker();
 
+        let p  = dt.person_from_email_address("csp@csperkins.org").await?;
+        assert_eq!(p.id,   20209);
+        assert_eq!(p.name, "Colin Perkins");
 
+        let h  = dt.person_aliases(&p).await?.try_collect::<Vec<_>>().await?;
+        assert_eq!(h.len(), 2);
 
-    // ----------------------------------------------------------------------------------------------------------------------------
-    // Datatracker API endpoints returning information about working groups:
-    //   https://datatracker.ietf.org/api/v1/group/group/                               - list of groups
-    //   https://datatracker.ietf.org/api/v1/group/group/2161/                          - info about group 2161
-    //   https://datatracker.ietf.org/api/v1/group/grouphistory/?group=2161             - history
-    //   https://datatracker.ietf.org/api/v1/group/groupurl/?group=2161                 - URLs
-    //   https://datatracker.ietf.org/api/v1/group/groupevent/?group=2161               - events
-    //   https://datatracker.ietf.org/api/v1/group/groupmilestone/?group=2161           - Current milestones
-    //   https://datatracker.ietf.org/api/v1/group/groupmilestonehistory/?group=2161    - Previous milestones
-    //   https://datatracker.ietf.org/api/v1/group/milestonegroupevent/?group=2161      - changed milestones
-    //   https://datatracker.ietf.org/api/v1/group/role/?group=2161                     - The current WG chairs and ADs of a group
-    //   https://datatracker.ietf.org/api/v1/group/role/?person=20209                   - Groups a person is currently involved with
-    //   https://datatracker.ietf.org/api/v1/group/role/?email=csp@csperkins.org        - Groups a person is currently involved with
-    //   https://datatracker.ietf.org/api/v1/group/rolehistory/?group=2161              - The previous WG chairs and ADs of a group
-    //   https://datatracker.ietf.org/api/v1/group/rolehistory/?person=20209            - Groups person was previously involved with
-    //   https://datatracker.ietf.org/api/v1/group/rolehistory/?email=csp@csperkins.org - Groups person was previously involved with
-    //   https://datatracker.ietf.org/api/v1/group/changestategroupevent/?group=2161    - Group state changes
-    //   https://datatracker.ietf.org/api/v1/group/groupstatetransitions                - ???
+        Ok(())
+    }
 
 
+    #[tokio::test]
+    async fn test_async_related_documents() -> DTResult<()> {
+        let dt = synthetic_async_datatracker();
+
+        let doc = dt.document(&DocumentUri::from_name("draft-ietf-avtcore-rtp-multi-stream")).await?;
+        assert_eq!(dt.related_documents_to(&doc).await?.try_collect::<Vec<_>>().await?.len(), 0);
+        assert_eq!(dt.referenced_by(&doc).await?.try_collect::<Vec<_>>().await?.len(),        0);
+        assert!(dt.related_closure(&doc, &[Relationship::Replaces], RelationshipDirection::Incoming).await?.is_empty());
+        Ok(())
+    }
 
     // ----------------------------------------------------------------------------------------------------------------------------
-    // Datatracker API endpoints returning information about meetings:
-    //   https://datatracker.ietf.org/api/v1/meeting/meeting/                        - list of meetings
-    //   https://datatracker.ietf.org/api/v1/meeting/meeting/747/                    - information about meeting number 747
-    //   https://datatracker.ietf.org/api/v1/meeting/session/                        - list of all sessions in meetings
-    //   https://datatracker.ietf.org/api/v1/meeting/session/25886/                  - a session in a meeting
-    //   https://datatracker.ietf.org/api/v1/meeting/session/?meeting=747            - sessions in meeting number 747
-    //   https://datatracker.ietf.org/api/v1/meeting/session/?meeting=747&group=2161 - sessions in meeting number 747 for group 2161
-    //   https://datatracker.ietf.org/api/v1/meeting/schedtimesessassignment/59003/  - a schededuled session within a meeting
-    //   https://datatracker.ietf.org/api/v1/meeting/timeslot/9480/                  - a time slot within a meeting (time, duration, location)
-    //   https://datatracker.ietf.org/api/v1/meeting/schedule/791/                   - a draft of the meeting agenda
-    //   https://datatracker.ietf.org/api/v1/meeting/room/537/                       - a room at a meeting
-    //   https://datatracker.ietf.org/api/v1/meeting/floorplan/14/                   - floor plan for a meeting venue
-    //   https://datatracker.ietf.org/api/v1/name/meetingtypename/
+    // Tests relating to queries:
 
+    #[test]
+    fn test_query_url() {
+        let dt = Datatracker::new();
 
+        assert_eq!(dt.query::<Person>().url(), "/api/v1/person/person/");
+
+        let q = dt.query::<Person>()
+                  .contains("name", "Perkins & Co")
+                  .icontains("ascii", "perkins")
+                  .gte("time", Utc.with_ymd_and_hms(2018, 3, 27, 14, 7, 36).unwrap())
+                  .lt("id", 30000)
+                  .is_in("id", &[20209, 104557])
+                  .isnull("user", false)
+                  .order_by("-time")
+                  .limit(50);
+        assert_eq!(q.url(), "/api/v1/person/person/?name__contains=Perkins%20%26%20Co&ascii__icontains=perkins\
+                             &time__gte=2018-03-27T14%3A07%3A36&id__lt=30000&id__in=20209,104557&user__isnull=false\
+                             &order_by=-time&limit=50");
+
+        let q = dt.query::<HistoricalEmail>().exact("address", "csp@isi.edu");
+        assert_eq!(q.url(), "/api/v1/person/historicalemail/?address=csp%40isi.edu");
+
+        let q = dt.documents()
+                  .group(&GroupUri::from_id(941))
+                  .doc_type(&DocType::Draft)
+                  .stream(&DocStream::Ietf)
+                  .state(&DocStateUri::from_id(1))
+                  .since(Utc.with_ymd_and_hms(2019, 1, 1, 0, 0, 0).unwrap());
+        assert_eq!(q.url(), "/api/v1/doc/document/?group=941&type=draft&stream=ietf&states=1&time__gte=2019-01-01T00%3A00%3A00");
+    }
 
-}
-// =================================================================================================================================
-// Test suite:
 
-#[cfg(test)]
-mod ietfdata_tests {
-    use super::*;
+    #[test]
+    fn test_query_people() -> DTResult<()> {
+        let dt = synthetic_datatracker();
+
+        let people = dt.query::<Person>()
+                       .contains("name", "Colin Perkins")
+                       .isnull("user", false)
+                       .order_by("id")
+                       .fetch()?
+                       .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(people[0].id,   20209);
+        assert_eq!(people[0].name, "Colin Perkins");
+
+        Ok(())
+    }
 
     // ----------------------------------------------------------------------------------------------------------------------------
     // Tests relating to email:
 
     #[test]
     fn test_email() -> DTResult<()> {
-        let dt = Datatracker::new();
+        let dt = synthetic_datatracker();
 
         let e  = dt.email(&EmailUri("/api/v1/person/email/csp@csperkins.org/".to_string()))?;
         assert_eq!(e.resource_uri, EmailUri("/api/v1/person/email/csp@csperkins.org/".to_string()));
         assert_eq!(e.address,      "csp@csperkins.org");
         assert_eq!(e.person,       PersonUri("/api/v1/person/person/20209/".to_string()));
-        assert_eq!(e.time,         Utc.ymd(1970, 1, 2).and_hms(07, 59, 59));
+        assert_eq!(e.time,         Utc.with_ymd_and_hms(1970, 1, 2, 7, 59, 59).unwrap());
         assert_eq!(e.primary,      true);
         assert_eq!(e.active,       true);
 
@@ -341,14 +1640,14 @@ mod ietfdata_tests {
 
     #[test]
     fn test_email_from_address() -> DTResult<()> {
-        let dt = Datatracker::new();
+        let dt = synthetic_datatracker();
 
         // Lookup an address that exists:
         let e  = dt.email_from_address("csp@csperkins.org")?;
         assert_eq!(e.resource_uri, EmailUri("/api/v1/person/email/csp@csperkins.org/".to_string()));
         assert_eq!(e.address,      "csp@csperkins.org");
         assert_eq!(e.person,       PersonUri("/api/v1/person/person/20209/".to_string()));
-        assert_eq!(e.time,         Utc.ymd(1970, 1, 2).and_hms(07, 59, 59));
+        assert_eq!(e.time,         Utc.with_ymd_and_hms(1970, 1, 2, 7, 59, 59).unwrap());
         assert_eq!(e.primary,      true);
         assert_eq!(e.active,       true);
 
@@ -361,7 +1660,7 @@ mod ietfdata_tests {
 
     #[test]
     fn test_email_history_for_address() -> DTResult<()> {
-        let dt = Datatracker::new();
+        let dt = synthetic_datatracker();
 
         let h  = dt.email_history_for_address("csp@isi.edu")?.collect::<Result<Vec<_>, _>>()?;
         assert_eq!(h.len(), 8);
@@ -383,7 +1682,7 @@ mod ietfdata_tests {
 /*
     #[test]
     fn test_email_history_for_person() -> DTResult<()> {
-        let dt = Datatracker::new();
+        let dt = synthetic_datatracker();
         let p  = dt.person_from_email_address("csp@csperkins.org")?;
         for h in dt.email_history_for_person(&p) {
             println!("{:?}", h);
@@ -395,7 +1694,7 @@ mod ietfdata_tests {
 
     #[test]
     fn test_person() -> DTResult<()> {
-        let dt = Datatracker::new();
+        let dt = synthetic_datatracker();
 
         let p  = dt.person(&PersonUri("/api/v1/person/person/20209/".to_string()))?;
         assert_eq!(p.id,              20209);
@@ -404,7 +1703,7 @@ mod ietfdata_tests {
         assert_eq!(p.name_from_draft, Some("Colin Perkins".to_string()));
         assert_eq!(p.ascii,           "Colin Perkins");
         assert_eq!(p.ascii_short,     Some("".to_string()));
-        assert_eq!(p.time,            Utc.ymd(2012,2,26).and_hms(8,3,54));
+        assert_eq!(p.time,            Utc.with_ymd_and_hms(2012,2,26, 8,3,54).unwrap());
         assert_eq!(p.photo,           Some("https://www.ietf.org/lib/dt/media/photo/csp-square.jpg".to_string()));
         assert_eq!(p.photo_thumb,     Some("https://www.ietf.org/lib/dt/media/photo/csp-square_GDMMZmn.jpg".to_string()));
         assert_eq!(p.user,            Some("".to_string()));
@@ -413,7 +1712,7 @@ mod ietfdata_tests {
 
     #[test]
     fn test_person_from_email() -> DTResult<()> {
-        let dt = Datatracker::new();
+        let dt = synthetic_datatracker();
 
         let p  = dt.person_from_email(&EmailUri("/api/v1/person/email/csp@csperkins.org/".to_string()))?;
         assert_eq!(p.id,   20209);
@@ -426,7 +1725,7 @@ mod ietfdata_tests {
 
     #[test]
     fn test_person_from_email_address() -> DTResult<()> {
-        let dt = Datatracker::new();
+        let dt = synthetic_datatracker();
 
         let p  = dt.person_from_email_address("csp@csperkins.org")?;
         assert_eq!(p.id,   20209);
@@ -439,7 +1738,7 @@ mod ietfdata_tests {
 /*
     #[test]
     fn test_people() {
-        let dt = Datatracker::new();
+        let dt = synthetic_datatracker();
         let people = dt.people();
         for person in people.into_iter() {
             println!("{:?}", person);
@@ -450,7 +1749,7 @@ mod ietfdata_tests {
 
     #[test]
     fn test_people_with_name() -> DTResult<()> {
-        let dt = Datatracker::new();
+        let dt = synthetic_datatracker();
 
         let people = dt.people_with_name("Colin Perkins")?.collect::<Result<Vec<_>, _>>()?;
         assert_eq!(people[0].id,   20209);
@@ -462,7 +1761,7 @@ mod ietfdata_tests {
 
     #[test]
     fn test_people_with_name_containing() -> DTResult<()> {
-        let dt = Datatracker::new();
+        let dt = synthetic_datatracker();
 
         let people = dt.people_with_name_containing("Perkins")?.collect::<Result<Vec<_>, _>>()?;
         assert_eq!(people.len(), 8); // As of 2022-05-02, there are 8 people named Perkins in the datatracker.
@@ -473,7 +1772,7 @@ mod ietfdata_tests {
 
     #[test]
     fn test_person_history() -> DTResult<()> {
-        let dt = Datatracker::new();
+        let dt = synthetic_datatracker();
 
         let p  = dt.person_from_email_address("csp@csperkins.org")?;
         let h  = dt.person_history(&p)?.collect::<Result<Vec<_>, _>>()?;
@@ -495,7 +1794,7 @@ mod ietfdata_tests {
 
     #[test]
     fn test_person_aliases() -> DTResult<()> {
-        let dt = Datatracker::new();
+        let dt = synthetic_datatracker();
 
         let p  = dt.person_from_email_address("csp@csperkins.org")?;
         let h  = dt.person_aliases(&p)?.collect::<Result<Vec<_>, _>>()?;
@@ -510,8 +1809,200 @@ mod ietfdata_tests {
     // Tests relating to documents:
 
     #[test]
-    fn test_doc_state() -> DTResult<()> {
+    fn test_document() -> DTResult<()> {
+        let dt = synthetic_datatracker();
+
+        let doc = dt.document_from_rfc(3550)?;
+        assert_eq!(doc.name,  "draft-ietf-avt-rtp-new");
+        assert_eq!(doc.title, "RTP: A Transport Protocol for Real-Time Applications");
+        assert_eq!(doc.group, Some(GroupUri::from_id(941)));
+
+        let doc = dt.document_from_draft("draft-ietf-avt-rtp-new")?;
+        assert_eq!(doc.resource_uri,       DocumentUri::from_name("draft-ietf-avt-rtp-new"));
+        assert_eq!(doc.pages,              Some(104));
+        assert_eq!(doc.expires,            None);
+        assert_eq!(doc.ad,                 Some(PersonUri::from_id(2515)));
+        assert_eq!(doc.doc_type,           DocType::Draft);
+        assert_eq!(doc.stream,             Some(DocStream::Ietf));
+        assert_eq!(doc.std_level,          Some(StdLevel::InternetStandard));
+        assert_eq!(doc.intended_std_level, Some(IntendedStdLevel::InternetStandard));
+        assert_eq!(doc.tags,               vec![]);
+
+        let tags : Vec<DocTag> = serde_json::from_str(r#"["/api/v1/name/doctagname/app-min/", "/api/v1/name/doctagname/new-tag/"]"#).unwrap();
+        assert_eq!(tags, vec![DocTag::ApprovedInMinutes, DocTag::Unknown("new-tag".to_string())]);
+
+        match dt.document_from_rfc(9999) {
+            Err(DatatrackerError::NotFound) => {}
+            _ => panic!("expected NotFound")
+        }
+
+        let drafts = dt.documents()
+                       .group(&GroupUri::from_id(941))
+                       .doc_type(&DocType::Draft)
+                       .until(Utc.with_ymd_and_hms(2004, 1, 1, 0, 0, 0).unwrap())
+                       .fetch()?
+                       .collect::<Result<Vec<_>, _>>()?;
+        assert!(drafts.iter().any(|d| d.name == "draft-ietf-avt-rtp-new"));
+        Ok(())
+    }
+
+
+    #[test]
+    fn test_document_authors() -> DTResult<()> {
+        let dt = synthetic_datatracker();
+
+        let doc     = dt.document_from_rfc(3550)?;
+        let authors = dt.document_authors(&doc)?.collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(authors.len(),          4);
+        assert_eq!(authors[0].order,       1);
+        assert_eq!(authors[0].email,       None);
+        assert_eq!(authors[1].email,       Some(EmailUri::from_address("casner@acm.org")));
+        assert_eq!(authors[1].affiliation, "Packet Design");
+
+        // RFC 8834 is by Colin Perkins, Magnus Westerlund, and Joerg Ott
+        let doc     = dt.document_from_rfc(8834)?;
+        let authors = dt.document_authors(&doc)?.collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(authors.len(),     3);
+        assert_eq!(authors[0].person, PersonUri::from_id(20209));
+
+        let person = dt.person(&PersonUri::from_id(20209))?;
+        let docs   = dt.documents_authored_by(&person)?.collect::<Result<Vec<_>, _>>()?;
+        assert!(docs.iter().any(|a| a.document == doc.resource_uri));
+        Ok(())
+    }
+
+
+    #[test]
+    fn test_doc_aliases() -> DTResult<()> {
+        let dt = synthetic_datatracker();
+
+        let doc = dt.document_from_alias("rfc3550")?;
+        assert_eq!(doc.name, "draft-ietf-avt-rtp-new");
+        assert_eq!(dt.rfc_number(&doc)?, Some(3550));
+
+        let docs = dt.documents_from_alias("bcp14")?;
+        let rfcs = docs.iter().map(|d| dt.rfc_number(d)).collect::<Result<Vec<_>, _>>()?;
+        assert!(rfcs.contains(&Some(2119)));
+        assert!(rfcs.contains(&Some(8174)));
+        Ok(())
+    }
+
+
+    #[test]
+    fn test_doc_events() -> DTResult<()> {
+        let dt = synthetic_datatracker();
+
+        let doc    = dt.document_from_draft("draft-ietf-avt-rtp-new")?;
+        let events = dt.doc_events_for_document(&doc)?;
+        assert!(events.iter().any(|e| matches!(e, DocEvent::NewRevision(_))));
+        assert!(events.windows(2).all(|w| w[0].time() <= w[1].time()));
+
+        assert_eq!(events.len(), 3);
+        match events[1] {
+            DocEvent::Base(ref e) => assert_eq!(e.event_type, "added_comment"),
+            _ => panic!("expected DocEvent::Base")
+        }
+        match events[2] {
+            DocEvent::State(ref e) => assert_eq!(e.state, Some(DocStateUri::from_id(3))),
+            _ => panic!("expected DocEvent::State")
+        }
+        assert_eq!(events[2].id(),         60145);
+        assert_eq!(events[2].event_type(), "changed_state");
+        assert_eq!(events[2].by(),         &PersonUri::from_id(2515));
+
+        // Deleted objects are listed separately
+        let deleted : DeletedEvent = serde_json::from_str(
+                          r#"{"id": 5, "resource_uri": "/api/v1/doc/deletedevent/5/", "content_type": "/api/v1/contenttypes/contenttype/29/",
+                              "json": "[{\"model\": \"doc.docalias\", \"pk\": 1}]", "by": "/api/v1/person/person/2515/",
+                              "time": "2003-05-03T12:00:00+00:00"}"#).unwrap();
+        assert_eq!(deleted.content_type, ContentTypeUri("/api/v1/contenttypes/contenttype/29/".to_string()));
+        assert_eq!(dt.deleted_events().since(deleted.time).url(), "/api/v1/doc/deletedevent/?time__gte=2003-05-03T12%3A00%3A00");
+        Ok(())
+    }
+
+
+    #[test]
+    fn test_current_ballot() -> DTResult<()> {
+        let dt = synthetic_datatracker();
+
+        let doc    = dt.document_from_draft("draft-ietf-avtcore-rtp-circuit-breakers")?;
+        let ballot = dt.current_ballot(&doc)?.unwrap();
+        assert_eq!(dt.ballot_type(&ballot.ballot.ballot_type)?.slug, "approve");
+        assert!(!ballot.positions.is_empty());
+        assert_eq!(ballot.positions.iter().map(|p| &p.balloter).collect::<HashSet<_>>().len(), ballot.positions.len());
+
+        assert_eq!(ballot.ballot.id, 547021);
+        assert!(!ballot.closed);
+        assert_eq!(ballot.positions.iter().map(|p| p.id).collect::<Vec<_>>(), vec![547102, 547104, 547103]);
+
+        let pos = ballot.position(&PersonUri::from_id(105519)).unwrap();
+        assert_eq!(pos.pos,          BallotPosition::NoObjection);
+        assert_eq!(pos.comment,      "Thanks");
+        assert_eq!(pos.comment_time, Some(Utc.with_ymd_and_hms(2016, 1, 5, 12, 0, 0).unwrap()));
+        assert!(ballot.position(&PersonUri::from_id(20209)).is_none());
+
+        let blocking = ballot.blocking_positions();
+        assert_eq!(blocking.len(),       1);
+        assert_eq!(blocking[0].balloter, PersonUri::from_id(101568));
+        assert_eq!(blocking[0].discuss,  "Security issue");
+
+        let ballot_type = dt.get(&ballot.ballot.ballot_type)?;
+        assert_eq!(ballot_type.slug,      "approve");
+        assert_eq!(ballot_type.doc_type,  Some(DocType::Draft));
+        assert_eq!(ballot_type.positions.len(), 6);
+        Ok(())
+    }
+
+
+    #[test]
+    fn test_related_documents() -> DTResult<()> {
+        let dt = synthetic_datatracker();
+
+        // RFC 3550 obsoletes RFC 1889, and is updated by RFCs 5761 and 8083
+        let rfc1889 = dt.document_from_rfc(1889)?;
+        let rfc3550 = dt.document_from_rfc(3550)?;
+        let newer   = dt.related_closure(&rfc1889, &[Relationship::Obsoletes, Relationship::Updates], RelationshipDirection::Incoming)?;
+        assert!(newer.iter().any(|d| d.resource_uri == rfc3550.resource_uri));
+        assert_eq!(newer.iter().map(|d| d.name.as_str()).collect::<Vec<_>>(),
+                   vec!["draft-ietf-avt-rtp-new", "draft-ietf-avt-rtp-and-rtcp-mux", "draft-ietf-avtcore-rtp-circuit-breakers"]);
+
+        let refs = dt.references(&rfc3550)?.collect::<Result<Vec<_>, _>>()?;
+        assert!(refs.iter().any(|r| r.relationship == Relationship::NormativeReference));
+
+        let doc   = dt.document_from_rfc(8083)?;
+        let chain = dt.replaces_chain(&doc)?;
+        assert_eq!(chain.iter().map(|d| d.name.as_str()).collect::<Vec<_>>(), vec!["draft-perkins-avtcore-rtp-circuit-breakers"]);
+
+        // A document with no aliases cannot be the target of a relationship
+        let doc = dt.document(&DocumentUri::from_name("draft-ietf-avtcore-rtp-multi-stream"))?;
+        assert_eq!(dt.related_documents_to(&doc)?.count(), 0);
+        assert_eq!(dt.referenced_by(&doc)?.count(),        0);
+        assert!(dt.related_closure(&doc, &[Relationship::Replaces], RelationshipDirection::Incoming)?.is_empty());
+        Ok(())
+    }
+
+
+    #[test]
+    fn test_relationship() -> DTResult<()> {
+        let rel : Relationship = serde_json::from_str(r#""/api/v1/name/docrelationshipname/obs/""#).unwrap();
+        assert_eq!(rel,        Relationship::Obsoletes);
+        assert_eq!(rel.slug(), "obs");
+        assert_eq!(rel.uri(),  "/api/v1/name/docrelationshipname/obs/");
+
+        let rel : Relationship = serde_json::from_str(r#""/api/v1/name/docrelationshipname/new-type/""#).unwrap();
+        assert_eq!(rel, Relationship::Unknown("new-type".to_string()));
+        assert!(serde_json::from_str::<Relationship>(r#""/api/v1/name/doctypename/draft/""#).is_err());
+
         let dt = Datatracker::new();
+        let q  = dt.query::<RelatedDocument>().relationships(&Relationship::references());
+        assert_eq!(q.url(), "/api/v1/doc/relateddocument/?relationship__in=refnorm,refinfo,refunk,refold");
+        Ok(())
+    }
+
+
+    #[test]
+    fn test_doc_state() -> DTResult<()> {
+        let dt = synthetic_datatracker();
 
         let uri = DocStateUri("/api/v1/doc/state/81/".to_string());
         let st  = dt.doc_state(&uri)?;
@@ -530,7 +2021,7 @@ mod ietfdata_tests {
 
     #[test]
     fn test_doc_states() -> DTResult<()> {
-        let dt = Datatracker::new();
+        let dt = synthetic_datatracker();
 
         let st = dt.doc_states()?.collect::<Result<Vec<_>, _>>()?;
         assert_eq!(st.len(), 180);
@@ -539,7 +2030,7 @@ mod ietfdata_tests {
 
     #[test]
     fn test_doc_state_type() -> DTResult<()> {
-        let dt = Datatracker::new();
+        let dt = synthetic_datatracker();
 
         let uri = DocStateTypeUri("/api/v1/doc/statetype/draft/".to_string());
         let st  = dt.doc_state_type(&uri)?;
@@ -552,7 +2043,7 @@ mod ietfdata_tests {
 
     #[test]
     fn test_doc_state_types() -> DTResult<()> {
-        let dt = Datatracker::new();
+        let dt = synthetic_datatracker();
 
         let st = dt.doc_state_types()?.collect::<Result<Vec<_>, _>>()?;
         assert_eq!(st.len(), 34);
@@ -593,6 +2084,264 @@ mod ietfdata_tests {
 
         Ok(())
     }
+
+
+    #[test]
+    fn test_state_machine() -> DTResult<()> {
+        let dt   = states_datatracker();
+        let sm   = dt.state_machine()?;
+        let iesg = DocStateTypeUri("/api/v1/doc/statetype/draft-iesg/".to_string());
+        assert_eq!(sm.state_types().count(), 2);
+        assert_eq!(sm.states(&iesg).iter().map(|s| s.id).collect::<Vec<_>>(), vec![16, 13, 7]);
+
+        assert!( sm.is_valid_transition(&DocStateUri::from_id(16), &DocStateUri::from_id(13)));
+        assert!( sm.is_valid_transition(&DocStateUri::from_id(13), &DocStateUri::from_id(16)));
+        assert!(!sm.is_valid_transition(&DocStateUri::from_id(16), &DocStateUri::from_id(7)));
+        assert!(!sm.is_valid_transition(&DocStateUri::from_id(16), &DocStateUri::from_id(99)));
+        assert!(!sm.is_valid_transition(&DocStateUri::from_id(13), &DocStateUri::from_id(2)));
+
+        let reachable = sm.reachable_states(&DocStateUri::from_id(16)).iter().map(|s| s.id).collect::<Vec<_>>();
+        assert_eq!(reachable, vec![13, 7, 16]);
+        assert!(sm.reachable_states(&DocStateUri::from_id(7)).is_empty());
+        assert_eq!(sm.terminal_states(&iesg).iter().map(|s| s.id).collect::<Vec<_>>(), vec![7]);
+
+        let doc     = dt.document(&DocumentUri::from_name("draft-ietf-avt-rtp-new"))?;
+        let current = sm.current_states(&doc);
+        assert_eq!(current.len(), 2);
+        assert_eq!(current[&DocStateTypeUri("/api/v1/doc/statetype/draft/".to_string())].slug, "expired");
+        assert_eq!(sm.current_state(&doc, &iesg).map(|s| s.slug.as_str()), Some("pub"));
+
+        let draft = DocStateTypeUri("/api/v1/doc/statetype/draft/".to_string());
+        assert_eq!(sm.to_dot(&draft),
+                   "digraph \"draft\" {\n    label=\"State\";\n    s1 [label=\"Active\"];\n    \
+                    s2 [label=\"Expired\", style=dashed, color=grey, fontcolor=grey];\n}\n");
+        assert_eq!(sm.to_mermaid(&draft),
+                   "stateDiagram-v2\n    s1 : Active\n    s2 : Expired\n    \
+                    classDef unused stroke-dasharray: 5 5, color: grey\n    class s2 unused\n");
+        assert!(sm.to_dot(&iesg).ends_with("    s16 -> s13;\n    s13 -> s16;\n    s13 -> s7;\n}\n"));
+        assert!(sm.to_mermaid(&iesg).ends_with("    s16 --> s13\n    s13 --> s16\n    s13 --> s7\n"));
+        Ok(())
+    }
+
+    // ----------------------------------------------------------------------------------------------------------------------------
+    // Tests relating to submissions:
+
+    #[test]
+    fn test_submissions() -> DTResult<()> {
+        let dt = synthetic_datatracker();
+
+        let doc  = dt.document_from_rfc(8083)?;
+        let subs = dt.submissions_for_document(&doc)?.collect::<Result<Vec<_>, _>>()?;
+        assert!(subs.iter().any(|s| s.rev == "00"));
+        assert!(subs.iter().all(|s| s.name == doc.name));
+        assert_eq!(subs[0].replaced_drafts(), vec!["draft-perkins-avtcore-rtp-circuit-breakers"]);
+
+        let checks = dt.submission_checks(&subs[subs.len() - 1])?.collect::<Result<Vec<_>, _>>()?;
+        assert!(checks.iter().any(|c| c.checker == "idnits check"));
+
+        let mut sub = dt.submission(&subs[subs.len() - 1].resource_uri)?;
+        assert_eq!(sub.state,             SubmissionState::Posted);
+        assert_eq!(sub.submission_date,   NaiveDate::from_ymd_opt(2016, 3, 21));
+        assert_eq!(sub.file_types(),      vec![".txt", ".xml"]);
+        assert_eq!(sub.replaced_drafts(), Vec::<&str>::new());
+
+        let authors = sub.parse_authors().unwrap();
+        assert_eq!(authors.len(),     2);
+        assert_eq!(authors[0].name,   "Colin Perkins");
+        assert_eq!(authors[0].email,  Some("csp@csperkins.org".to_string()));
+        assert_eq!(authors[1].name,   "Varun Singh");
+        assert_eq!(authors[1].email,  None);
+
+        // Python escapes for non-ASCII characters, including those outside the Basic Multilingual Plane
+        sub.authors = r"[{u'name': u'J\xf6rg Ott', u'email': u'jo@acm.org'}, {u'name': u'\u039c\u03b1\u03c1\u03af\u03b1 \U0001f600', u'email': None}, {u'name': 'It\'s \\', u'email': None}]".to_string();
+        let authors = sub.parse_authors().unwrap();
+        assert_eq!(authors[0].name,   "Jörg Ott");
+        assert_eq!(authors[1].name,   "Μαρία \u{1f600}");
+        assert_eq!(authors[2].name,   "It's \\");
+
+        let q = dt.submissions()
+                  .state(&SubmissionState::Posted)
+                  .submitted_since(NaiveDate::from_ymd_opt(2016, 1, 1).unwrap())
+                  .submitted_until(NaiveDate::from_ymd_opt(2017, 1, 1).unwrap());
+        assert_eq!(q.url(), "/api/v1/submit/submission/?state=posted&submission_date__gte=2016-01-01&submission_date__lt=2017-01-01");
+
+        // Submissions that were never posted have no draft
+        let subs = dt.submissions()
+                     .submitted_since(NaiveDate::from_ymd_opt(2016, 3, 20).unwrap())
+                     .submitted_until(NaiveDate::from_ymd_opt(2016, 3, 22).unwrap())
+                     .fetch()?
+                     .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(subs.len(),     2);
+        assert_eq!(subs[0].state,  SubmissionState::Cancelled);
+        assert_eq!(subs[0].draft,  None);
+        assert_eq!(subs[1].draft,  Some(doc.resource_uri.clone()));
+        Ok(())
+    }
+
+    // ----------------------------------------------------------------------------------------------------------------------------
+    // Tests relating to groups:
+
+    #[test]
+    fn test_group() -> DTResult<()> {
+        let dt = synthetic_datatracker();
+
+        let group = dt.group_from_acronym("avtcore")?;
+        assert_eq!(group.resource_uri, GroupUri::from_id(1683));
+        assert_eq!(group.charter,      Some(DocumentUri::from_name("charter-ietf-avtcore")));
+        assert_eq!(group.ad,           None);
+        assert_eq!(dt.group(&group.resource_uri)?.acronym, "avtcore");
+        assert!(matches!(dt.group_from_acronym("none"), Err(DatatrackerError::NotFound)));
+
+        assert_eq!(dt.group_type(&group.group_type)?.slug,         "wg");
+        assert_eq!(dt.group_type(&group.group_type)?.verbose_name, "Working Group");
+        assert_eq!(dt.group_state(&group.state)?.name,             "Active");
+
+        let area = dt.group(group.parent.as_ref().unwrap())?;
+        assert_eq!(area.acronym, "art");
+        assert_eq!(area.charter, None);
+        assert_eq!(area.parent,  Some(GroupUri::from_id(1)));
+
+        let wgs = dt.groups().group_type(&GroupTypeUri::from_slug("wg")).parent(&area.resource_uri).fetch()?.collect::<DTResult<Vec<_>>>()?;
+        assert!(wgs.iter().any(|wg| wg.acronym == "avtcore"));
+
+        let q = dt.groups()
+                  .group_type(&GroupTypeUri::from_slug("wg"))
+                  .state(&GroupStateUri::from_slug("active"))
+                  .parent(&area.resource_uri)
+                  .since(Utc.with_ymd_and_hms(2019, 1, 1, 0, 0, 0).unwrap());
+        assert_eq!(q.url(), "/api/v1/group/group/?type=wg&state=active&parent=2&time__gte=2019-01-01T00%3A00%3A00");
+        Ok(())
+    }
+
+
+    #[test]
+    fn test_roles() -> DTResult<()> {
+        let dt = synthetic_datatracker();
+
+        let person = dt.person(&PersonUri::from_id(20209))?;
+        let roles  = dt.role_history_for_person(&person)?.collect::<DTResult<Vec<_>>>()?;
+        assert!(roles.iter().any(|r| r.name == RoleName::Chair));
+
+        let group  = dt.group_from_acronym("avtcore")?;
+        let chairs = dt.group_roles(&group, &RoleName::Chair)?.collect::<DTResult<Vec<_>>>()?;
+        assert_eq!(chairs.len(),    1);
+        assert_eq!(chairs[0].name,  RoleName::Chair);
+        assert_eq!(chairs[0].email, EmailUri::from_address("avtcore-chairs@ietf.org"));
+
+        let now  = Utc.with_ymd_and_hms(2021, 1, 1, 0, 0, 0).unwrap();
+        let then = Utc.with_ymd_and_hms(2015, 6, 1, 0, 0, 0).unwrap();
+        let old  = Utc.with_ymd_and_hms(2000, 1, 1, 0, 0, 0).unwrap();
+        assert_eq!(dt.group_role_holders_at(&group, &RoleName::Chair, now)?,  vec![PersonUri::from_id(105873)]);
+        assert_eq!(dt.group_role_holders_at(&group, &RoleName::Chair, then)?, vec![PersonUri::from_id(20209)]);
+        assert_eq!(dt.group_role_holders_at(&group, &RoleName::Chair, old)?,  vec![]);
+
+        let q = dt.roles().person(&PersonUri::from_id(20209)).name(&RoleName::AreaDirector);
+        assert_eq!(q.url(), "/api/v1/group/role/?person=20209&name=ad");
+        Ok(())
+    }
+
+
+    #[test]
+    fn test_milestones() -> DTResult<()> {
+        let dt = synthetic_datatracker();
+
+        let group      = dt.group_from_acronym("avtcore")?;
+        let milestones = dt.milestones_for_group(&group)?.collect::<DTResult<Vec<_>>>()?;
+        assert!(milestones.windows(2).all(|w| w[0].due <= w[1].due));
+        for milestone in milestones.iter().take(3) {
+            assert!(!dt.milestone_due_dates(milestone)?.is_empty());
+        }
+
+        let overdue = dt.overdue_milestones(&group, NaiveDate::from_ymd_opt(2016, 1, 1).unwrap())?;
+        assert_eq!(overdue.len(),     1);
+        assert_eq!(overdue[0].id,     6412);
+        assert_eq!(overdue[0].state,  GroupMilestoneState::Active);
+        assert_eq!(overdue[0].docs,   vec![DocumentUri::from_name("draft-ietf-avtcore-rtp-circuit-breakers")]);
+        assert!(!overdue[0].is_overdue(NaiveDate::from_ymd_opt(2015, 6, 30).unwrap()));
+
+        let due_dates = dt.milestone_due_dates(&overdue[0])?;
+        assert_eq!(due_dates, vec![(Utc.with_ymd_and_hms(2014, 1, 1, 0, 0, 0).unwrap(), NaiveDate::from_ymd_opt(2014, 12, 31).unwrap()),
+                                   (Utc.with_ymd_and_hms(2015, 3, 1, 0, 0, 0).unwrap(), NaiveDate::from_ymd_opt(2015,  6, 30).unwrap())]);
+        Ok(())
+    }
+
+
+    #[test]
+    fn test_group_history() -> DTResult<()> {
+        let dt = synthetic_datatracker();
+
+        let group    = dt.group_from_acronym("avtcore")?;
+        let timeline = dt.group_timeline(&group)?;
+        assert!(timeline.windows(2).all(|w| w[0].time() <= w[1].time()));
+
+        let chartered = timeline.iter().find(|e| matches!(e, GroupEvent::ChangeState(_))).map(|e| e.time()).unwrap();
+        let then      = dt.group_at(&group.resource_uri, chartered)?.unwrap();
+        assert_eq!(then.acronym, "avtcore");
+
+        assert_eq!(timeline.iter().map(|e| e.id()).collect::<Vec<_>>(), vec![3612, 5021, 6110]);
+        match timeline[0] {
+            GroupEvent::ChangeState(ref e) => assert_eq!(e.state, GroupStateUri::from_slug("proposed")),
+            _ => panic!("expected GroupEvent::ChangeState")
+        }
+        assert!(matches!(timeline[1], GroupEvent::Base(_)));
+        match timeline[2] {
+            GroupEvent::Milestone(ref e) => assert_eq!(e.milestone, GroupMilestoneUri::from_id(6412)),
+            _ => panic!("expected GroupEvent::Milestone")
+        }
+        assert_eq!(timeline[2].event_type(), "changed_milestone");
+
+        let uri   = group.resource_uri.clone();
+        let group = dt.group_at(&uri, Utc.with_ymd_and_hms(2021, 1, 1, 0, 0, 0).unwrap())?.unwrap();
+        assert_eq!(group.list_email, "avt@ietf.org");
+
+        let group = dt.group_at(&uri, Utc.with_ymd_and_hms(2010, 1, 1, 0, 0, 0).unwrap())?.unwrap();
+        assert_eq!(group.resource_uri, uri);
+        assert_eq!(group.list_email,   "avtcore@ietf.org");
+        assert_eq!(group.state,        GroupStateUri::from_slug("proposed"));
+        assert_eq!(group.parent,       Some(GroupUri::from_id(934)));
+        assert_eq!(group.ad,           Some(PersonUri::from_id(2515)));
+        assert_eq!(group.charter,      Some(DocumentUri::from_name("charter-ietf-avtcore")));
+        assert!(dt.group_at(&uri, Utc.with_ymd_and_hms(2000, 1, 1, 0, 0, 0).unwrap())?.is_none());
+        Ok(())
+    }
+
+
+    #[test]
+    fn test_group_tree() -> DTResult<()> {
+        let dt = synthetic_datatracker();
+
+        let tree = dt.group_tree()?;
+        let art  = dt.group_from_acronym("art")?;
+        let wgs  = tree.subtree(&art.resource_uri, &[GroupStateUri::from_slug("active")]).unwrap();
+        assert!(wgs.children.iter().any(|node| node.group.acronym == "avtcore"));
+        assert!(tree.ancestors(&dt.group_from_acronym("avtcore")?.resource_uri).iter().any(|g| g.acronym == "art"));
+
+        // The groups are listed in two pages, with avtcore, mmusic, and iccrg on the second
+        assert!(tree.group(&GroupUri::from_id(100)).is_some());
+        assert!(tree.group(&GroupUri::from_id(1150)).is_some());
+        assert_eq!(tree.children(&GroupUri::from_id(934)).len(), 1001);
+        assert_eq!(tree.children(&GroupUri::from_id(3)).iter().map(|g| g.acronym.as_str()).collect::<Vec<_>>(), vec!["iccrg"]);
+
+        // The IRTF is its own parent
+        assert_eq!(tree.roots().iter().map(|g| g.acronym.as_str()).collect::<Vec<_>>(), vec!["ietf", "irtf"]);
+        assert_eq!(tree.children(&GroupUri::from_id(2)).iter().map(|g| g.acronym.as_str()).collect::<Vec<_>>(), vec!["avtcore", "mmusic"]);
+        assert_eq!(tree.ancestors(&GroupUri::from_id(1683)).iter().map(|g| g.id).collect::<Vec<_>>(), vec![2, 1]);
+        assert!(tree.ancestors(&GroupUri::from_id(3)).is_empty());
+        assert!(tree.subtree(&GroupUri::from_id(99), &[]).is_none());
+
+        let active = tree.subtree(&GroupUri::from_id(1), &[GroupStateUri::from_slug("active")]).unwrap();
+        assert_eq!(active.to_text(), "ietf: Internet Engineering Task Force (ietf, active)\n  \
+                                        art: Applications and Real-Time Area (area, active)\n    \
+                                          avtcore: Audio/Video Transport Core Maintenance (wg, active)\n    \
+                                          mmusic: Multiparty Multimedia Session Control (wg, active)\n");
+        assert_eq!(active.to_json()["children"][0]["children"][0]["acronym"], "avtcore");
+        assert_eq!(active.to_json()["children"][0]["type"],                   "area");
+
+        let forest = tree.forest(&[]);
+        assert_eq!(forest.len(), 2);
+        assert_eq!(forest[0].children[0].children.len(), 2);
+        Ok(())
+    }
 }
 
 // =================================================================================================================================
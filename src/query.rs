@@ -0,0 +1,588 @@
+// Copyright (C) 2020 University of Glasgow
+//
+// Redistribution and use in source and binary forms, with or without
+// modification, are permitted provided that the following conditions
+// are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice,
+//    this list of conditions and the following disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright
+//    notice, this list of conditions and the following disclaimer in the
+//    documentation and/or other materials provided with the distribution.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+// AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+// IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE
+// ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE
+// LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR
+// CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF
+// SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS
+// INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN
+// CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE)
+// ARISING IN ANY WAY OUT OF THE USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE
+// POSSIBILITY OF SUCH DAMAGE.
+//
+// SPDX-License-Identifier: BSD-2-Clause
+
+// A query builder for the Datatracker list endpoints. The Datatracker uses
+// Django Tastypie, where a list can be filtered using query parameters of
+// the form field__op=value, for example:
+//   https://datatracker.ietf.org/api/v1/person/person/?name__contains=Perkins
+//   https://datatracker.ietf.org/api/v1/person/person/?time__gt=2018-03-27T14:07:36
+// For example:
+//   dt.query::<Person>().contains("name", "Perkins").order_by("-time").fetch()
+
+use std::collections::HashSet;
+use std::marker::PhantomData;
+
+use chrono::prelude::*;
+use futures::TryStreamExt;
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use serde::Deserialize;
+
+use crate::*;
+
+// =================================================================================================
+// The values that can be used in queries:
+
+// A value that can be used in a query parameter
+pub trait QueryValue {
+    fn to_query_value(&self) -> String;
+}
+
+macro_rules! query_value_display {
+    ($($t:ty),*) => {
+        $(
+            impl QueryValue for $t {
+                fn to_query_value(&self) -> String {
+                    self.to_string()
+                }
+            }
+        )*
+    }
+}
+
+query_value_display!(str, String, bool, u32, u64, i32, i64);
+
+impl QueryValue for DateTime<Utc> {
+    fn to_query_value(&self) -> String {
+        self.format("%Y-%m-%dT%H:%M:%S").to_string()
+    }
+}
+
+impl QueryValue for NaiveDate {
+    fn to_query_value(&self) -> String {
+        self.format("%Y-%m-%d").to_string()
+    }
+}
+
+impl<V: QueryValue + ?Sized> QueryValue for &V {
+    fn to_query_value(&self) -> String {
+        (*self).to_query_value()
+    }
+}
+
+// Everything except the RFC 3986 unreserved characters is percent-encoded
+const QUERY_ENCODE_SET : &AsciiSet = &NON_ALPHANUMERIC.remove(b'-').remove(b'.').remove(b'_').remove(b'~');
+
+fn encode(s : &str) -> String {
+    utf8_percent_encode(s, QUERY_ENCODE_SET).to_string()
+}
+
+// =================================================================================================
+// The query builder:
+
+pub struct Query<'a, D, T> {
+    dt     : &'a D,
+    params : Vec<(String, String)>,
+    _type  : PhantomData<T>
+}
+
+
+impl<'a, D, T> Query<'a, D, T>
+    where T: Endpoint
+{
+    pub fn new(dt : &'a D) -> Self {
+        Query {
+            dt,
+            params : Vec::new(),
+            _type  : PhantomData
+        }
+    }
+
+    // Add a raw Tastypie filter, e.g., filter("name", "contains", "Perkins").
+    // An empty op matches the value exactly.
+    pub fn filter<V: QueryValue>(mut self, field : &str, op : &str, value : V) -> Self {
+        let param = if op.is_empty() { field.to_string() } else { format!("{}__{}", field, op) };
+        self.params.push((param, encode(&value.to_query_value())));
+        self
+    }
+
+    pub fn exact<V: QueryValue>(self, field : &str, value : V) -> Self {
+        self.filter(field, "", value)
+    }
+
+    pub fn contains<V: QueryValue>(self, field : &str, value : V) -> Self {
+        self.filter(field, "contains", value)
+    }
+
+    pub fn icontains<V: QueryValue>(self, field : &str, value : V) -> Self {
+        self.filter(field, "icontains", value)
+    }
+
+    pub fn gt<V: QueryValue>(self, field : &str, value : V) -> Self {
+        self.filter(field, "gt", value)
+    }
+
+    pub fn gte<V: QueryValue>(self, field : &str, value : V) -> Self {
+        self.filter(field, "gte", value)
+    }
+
+    pub fn lt<V: QueryValue>(self, field : &str, value : V) -> Self {
+        self.filter(field, "lt", value)
+    }
+
+    pub fn lte<V: QueryValue>(self, field : &str, value : V) -> Self {
+        self.filter(field, "lte", value)
+    }
+
+    // Matches any of the given values (Tastypie's "in" filter)
+    pub fn is_in<V: QueryValue>(mut self, field : &str, values : &[V]) -> Self {
+        let values = values.iter().map(|v| encode(&v.to_query_value())).collect::<Vec<_>>().join(",");
+        self.params.push((format!("{}__in", field), values));
+        self
+    }
+
+    pub fn isnull(self, field : &str, isnull : bool) -> Self {
+        self.filter(field, "isnull", isnull)
+    }
+
+    // Objects with time at or after the given time
+    pub fn since(self, time : DateTime<Utc>) -> Self {
+        self.gte("time", time)
+    }
+
+    // Objects with time before the given time
+    pub fn until(self, time : DateTime<Utc>) -> Self {
+        self.lt("time", time)
+    }
+
+    // Sort the results by the given field. Prefix the field with "-" to
+    // sort in descending order.
+    pub fn order_by(self, field : &str) -> Self {
+        self.exact("order_by", field)
+    }
+
+    // The number of results fetched in each page
+    pub fn limit(self, limit : u32) -> Self {
+        self.exact("limit", limit)
+    }
+
+    // Start from the given offset in the list, e.g., to resume a crawl from
+    // the PaginatedList::offset() at which it stopped. Use order_by() to make
+    // sure the order of the list is the same each time.
+    pub fn offset(self, offset : u32) -> Self {
+        self.exact("offset", offset)
+    }
+
+    // The URL path and query, relative to the Datatracker base URL
+    pub fn url(&self) -> String {
+        if self.params.is_empty() {
+            T::PATH.to_string()
+        } else {
+            let params = self.params.iter().map(|(k, v)| format!("{}={}", encode(k), v)).collect::<Vec<_>>();
+            format!("{}?{}", T::PATH, params.join("&"))
+        }
+    }
+}
+
+
+// Filters for the group list:
+
+impl<'a, D> Query<'a, D, Group> {
+    // Groups of the given type, e.g., GroupTypeUri::from_slug("wg")
+    pub fn group_type(self, group_type : &GroupTypeUri) -> Self {
+        self.exact("type", group_type)
+    }
+
+    pub fn state(self, state : &GroupStateUri) -> Self {
+        self.exact("state", state)
+    }
+
+    // Groups that are children of the given group, e.g., the working groups
+    // in an area
+    pub fn parent(self, parent : &GroupUri) -> Self {
+        self.exact("parent", parent)
+    }
+}
+
+
+impl<'a, D> Query<'a, D, GroupHistory> {
+    // Snapshots of the given group
+    pub fn group(self, group : &GroupUri) -> Self {
+        self.exact("group", group)
+    }
+}
+
+
+impl<'a, D> Query<'a, D, GroupMilestone> {
+    pub fn group(self, group : &GroupUri) -> Self {
+        self.exact("group", group)
+    }
+
+    pub fn state(self, state : &GroupMilestoneState) -> Self {
+        self.exact("state", state)
+    }
+
+    // Milestones due before the given date
+    pub fn due_before(self, date : NaiveDate) -> Self {
+        self.lt("due", date)
+    }
+}
+
+
+// Filters for the lists of group events:
+
+// These are implemented for each type of group event, since a blanket
+// implementation of by() would conflict with that for DocEventEndpoint.
+macro_rules! group_event_filters {
+    ($($t:ty),*) => {
+        $(
+            impl<'a, D> Query<'a, D, $t> {
+                // Events for the given group
+                pub fn group(self, group : &GroupUri) -> Self {
+                    self.exact("group", group)
+                }
+
+                // Events caused by the given person
+                pub fn by(self, person : &PersonUri) -> Self {
+                    self.exact("by", person)
+                }
+            }
+        )*
+    }
+}
+
+group_event_filters!(BaseGroupEvent, ChangeStateGroupEvent, MilestoneGroupEvent);
+
+
+// Filters for the lists of roles:
+
+// The types of role
+pub trait RoleEndpoint : Endpoint {}
+
+impl RoleEndpoint for Role {}
+impl RoleEndpoint for RoleHistory {}
+
+impl<'a, D, T> Query<'a, D, T>
+    where T: RoleEndpoint
+{
+    pub fn name(self, name : &RoleName) -> Self {
+        self.exact("name", name)
+    }
+
+    pub fn person(self, person : &PersonUri) -> Self {
+        self.exact("person", person)
+    }
+
+    pub fn email(self, email : &EmailUri) -> Self {
+        self.exact("email", email)
+    }
+}
+
+
+impl<'a, D> Query<'a, D, Role> {
+    pub fn group(self, group : &GroupUri) -> Self {
+        self.exact("group", group)
+    }
+}
+
+
+impl<'a, D> Query<'a, D, RoleHistory> {
+    // Roles recorded in the given snapshot of a group
+    pub fn group(self, group : &GroupHistoryUri) -> Self {
+        self.exact("group", group)
+    }
+}
+
+
+// Filters for the document list:
+
+impl<'a, D> Query<'a, D, Document> {
+    pub fn group(self, group : &GroupUri) -> Self {
+        self.exact("group", group)
+    }
+
+    pub fn doc_type(self, doc_type : &DocType) -> Self {
+        self.exact("type", doc_type)
+    }
+
+    pub fn stream(self, stream : &DocStream) -> Self {
+        self.exact("stream", stream)
+    }
+
+    pub fn state(self, state : &DocStateUri) -> Self {
+        self.exact("states", state)
+    }
+}
+
+
+// Filters for the list of document authors:
+
+impl<'a, D> Query<'a, D, DocumentAuthor> {
+    pub fn document(self, doc : &Document) -> Self {
+        self.exact("document", doc.id)
+    }
+
+    pub fn person(self, person : &PersonUri) -> Self {
+        self.exact("person", person)
+    }
+
+    pub fn email(self, email : &EmailUri) -> Self {
+        self.exact("email", email)
+    }
+}
+
+
+// Filters for the list of relationships between documents:
+
+impl<'a, D> Query<'a, D, RelatedDocument> {
+    pub fn source(self, doc : &Document) -> Self {
+        self.exact("source", doc.id)
+    }
+
+    pub fn target(self, alias : &DocAlias) -> Self {
+        self.exact("target", alias.id)
+    }
+
+    pub fn relationship(self, relationship : &Relationship) -> Self {
+        self.exact("relationship", relationship)
+    }
+
+    pub fn relationships(self, relationships : &[Relationship]) -> Self {
+        self.is_in("relationship", relationships)
+    }
+}
+
+
+// Filters for the list of submissions:
+
+impl<'a, D> Query<'a, D, Submission> {
+    pub fn document(self, doc : &Document) -> Self {
+        self.exact("draft", doc.id)
+    }
+
+    pub fn group(self, group : &GroupUri) -> Self {
+        self.exact("group", group)
+    }
+
+    pub fn state(self, state : &SubmissionState) -> Self {
+        self.exact("state", state)
+    }
+
+    // Submissions on or after the given date
+    pub fn submitted_since(self, date : NaiveDate) -> Self {
+        self.gte("submission_date", date)
+    }
+
+    // Submissions before the given date
+    pub fn submitted_until(self, date : NaiveDate) -> Self {
+        self.lt("submission_date", date)
+    }
+}
+
+
+// Filters for the lists of document events:
+
+// The types of document event
+pub trait DocEventEndpoint : Endpoint {}
+
+macro_rules! doc_event_endpoint {
+    ($($t:ty),*) => {
+        $(
+            impl DocEventEndpoint for $t {}
+        )*
+    }
+}
+
+doc_event_endpoint!(BaseDocEvent, StateDocEvent, BallotDocEvent, NewRevisionDocEvent, SubmissionDocEvent, WriteupDocEvent,
+                    ConsensusDocEvent, BallotPositionDocEvent, ReviewRequestDocEvent, LastCallDocEvent, TelechatDocEvent,
+                    EditedAuthorsDocEvent, AddedMessageEvent);
+
+impl<'a, D, T> Query<'a, D, T>
+    where T: DocEventEndpoint
+{
+    // Events for the given document
+    pub fn doc(self, doc : &Document) -> Self {
+        self.exact("doc", doc.id)
+    }
+
+    // Events caused by the given person
+    pub fn by(self, person : &PersonUri) -> Self {
+        self.exact("by", person)
+    }
+}
+
+
+impl<'a, D> Query<'a, D, BallotPositionDocEvent> {
+    // Positions on the given ballot
+    pub fn ballot(self, ballot : &BallotDocEvent) -> Self {
+        self.exact("ballot", ballot.id)
+    }
+
+    // Positions taken by the given person
+    pub fn balloter(self, person : &PersonUri) -> Self {
+        self.exact("balloter", person)
+    }
+}
+
+
+impl<'a, D, T> Query<'a, D, T> {
+    // The same query on a different endpoint
+    fn with_endpoint<U>(&self) -> Query<'a, D, U> {
+        Query {
+            dt     : self.dt,
+            params : self.params.clone(),
+            _type  : PhantomData
+        }
+    }
+}
+
+
+impl<'a, T> Query<'a, Datatracker, T>
+    where T: Endpoint, for<'de> T: Deserialize<'de>
+{
+    pub fn fetch(self) -> DTResult<PaginatedList<'a, T>> {
+        PaginatedList::new(self.dt, self.url())
+    }
+}
+
+
+impl<'a> Query<'a, Datatracker, BaseDocEvent> {
+    // Fetch the events matching the query, each as its most specific type.
+    // This fetches the list of events, then makes the same query on the list
+    // of each more specific type of event that appears in it, so all matching
+    // events are fetched before returning. Don't use offset(), as the offsets
+    // differ between the lists.
+    pub fn fetch_typed(self) -> DTResult<Vec<DocEvent>> {
+        fn fetch_as<U, F>(query : &Query<Datatracker, BaseDocEvent>, paths : &HashSet<&str>, specific : &mut Vec<DocEvent>, f : F) -> DTResult<()>
+            where U: Endpoint, for<'de> U: Deserialize<'de>, F: Fn(U) -> DocEvent
+        {
+            if paths.contains(U::PATH) {
+                for event in query.with_endpoint::<U>().fetch()? {
+                    specific.push(f(event?));
+                }
+            }
+            Ok(())
+        }
+
+        let events       = self.with_endpoint::<BaseDocEvent>().fetch()?.collect::<DTResult<Vec<_>>>()?;
+        let paths        = DocEvent::subtype_paths(&events);
+        let mut specific = Vec::new();
+        fetch_as(&self, &paths, &mut specific, DocEvent::State)?;
+        fetch_as(&self, &paths, &mut specific, DocEvent::Ballot)?;
+        fetch_as(&self, &paths, &mut specific, DocEvent::NewRevision)?;
+        fetch_as(&self, &paths, &mut specific, DocEvent::Submission)?;
+        fetch_as(&self, &paths, &mut specific, DocEvent::Writeup)?;
+        fetch_as(&self, &paths, &mut specific, DocEvent::Consensus)?;
+        fetch_as(&self, &paths, &mut specific, DocEvent::BallotPosition)?;
+        fetch_as(&self, &paths, &mut specific, DocEvent::ReviewRequest)?;
+        fetch_as(&self, &paths, &mut specific, DocEvent::LastCall)?;
+        fetch_as(&self, &paths, &mut specific, DocEvent::Telechat)?;
+        fetch_as(&self, &paths, &mut specific, DocEvent::EditedAuthors)?;
+        fetch_as(&self, &paths, &mut specific, DocEvent::AddedMessage)?;
+        Ok(DocEvent::merge(events, specific))
+    }
+}
+
+
+impl<'a> Query<'a, Datatracker, BaseGroupEvent> {
+    // As fetch_typed() for document events
+    pub fn fetch_typed(self) -> DTResult<Vec<GroupEvent>> {
+        let events       = self.with_endpoint::<BaseGroupEvent>().fetch()?.collect::<DTResult<Vec<_>>>()?;
+        let paths        = GroupEvent::subtype_paths(&events);
+        let mut specific = Vec::new();
+        if paths.contains(ChangeStateGroupEvent::PATH) {
+            for event in self.with_endpoint::<ChangeStateGroupEvent>().fetch()? {
+                specific.push(GroupEvent::ChangeState(event?));
+            }
+        }
+        if paths.contains(MilestoneGroupEvent::PATH) {
+            for event in self.with_endpoint::<MilestoneGroupEvent>().fetch()? {
+                specific.push(GroupEvent::Milestone(event?));
+            }
+        }
+        Ok(GroupEvent::merge(events, specific))
+    }
+}
+
+
+impl<'a, T> Query<'a, AsyncDatatracker, T>
+    where T: Endpoint + Send + 'a, for<'de> T: Deserialize<'de>
+{
+    pub async fn fetch(self) -> DTResult<AsyncPaginatedList<'a, T>> {
+        let url = self.url();
+        AsyncPaginatedList::new(self.dt, url).await
+    }
+}
+
+
+impl<'a> Query<'a, AsyncDatatracker, BaseDocEvent> {
+    // As the blocking fetch_typed()
+    pub async fn fetch_typed(self) -> DTResult<Vec<DocEvent>> {
+        async fn fetch_as<U, F>(query : &Query<'_, AsyncDatatracker, BaseDocEvent>, paths : &HashSet<&str>, specific : &mut Vec<DocEvent>, f : F) -> DTResult<()>
+            where U: Endpoint + Send, for<'de> U: Deserialize<'de>, F: Fn(U) -> DocEvent
+        {
+            if paths.contains(U::PATH) {
+                let mut events = query.with_endpoint::<U>().fetch().await?;
+                while let Some(event) = events.try_next().await? {
+                    specific.push(f(event));
+                }
+            }
+            Ok(())
+        }
+
+        let events       = self.with_endpoint::<BaseDocEvent>().fetch().await?.try_collect::<Vec<_>>().await?;
+        let paths        = DocEvent::subtype_paths(&events);
+        let mut specific = Vec::new();
+        fetch_as(&self, &paths, &mut specific, DocEvent::State).await?;
+        fetch_as(&self, &paths, &mut specific, DocEvent::Ballot).await?;
+        fetch_as(&self, &paths, &mut specific, DocEvent::NewRevision).await?;
+        fetch_as(&self, &paths, &mut specific, DocEvent::Submission).await?;
+        fetch_as(&self, &paths, &mut specific, DocEvent::Writeup).await?;
+        fetch_as(&self, &paths, &mut specific, DocEvent::Consensus).await?;
+        fetch_as(&self, &paths, &mut specific, DocEvent::BallotPosition).await?;
+        fetch_as(&self, &paths, &mut specific, DocEvent::ReviewRequest).await?;
+        fetch_as(&self, &paths, &mut specific, DocEvent::LastCall).await?;
+        fetch_as(&self, &paths, &mut specific, DocEvent::Telechat).await?;
+        fetch_as(&self, &paths, &mut specific, DocEvent::EditedAuthors).await?;
+        fetch_as(&self, &paths, &mut specific, DocEvent::AddedMessage).await?;
+        Ok(DocEvent::merge(events, specific))
+    }
+}
+
+
+impl<'a> Query<'a, AsyncDatatracker, BaseGroupEvent> {
+    // As the blocking fetch_typed()
+    pub async fn fetch_typed(self) -> DTResult<Vec<GroupEvent>> {
+        let events       = self.with_endpoint::<BaseGroupEvent>().fetch().await?.try_collect::<Vec<_>>().await?;
+        let paths        = GroupEvent::subtype_paths(&events);
+        let mut specific = Vec::new();
+        if paths.contains(ChangeStateGroupEvent::PATH) {
+            let mut events = self.with_endpoint::<ChangeStateGroupEvent>().fetch().await?;
+            while let Some(event) = events.try_next().await? {
+                specific.push(GroupEvent::ChangeState(event));
+            }
+        }
+        if paths.contains(MilestoneGroupEvent::PATH) {
+            let mut events = self.with_endpoint::<MilestoneGroupEvent>().fetch().await?;
+            while let Some(event) = events.try_next().await? {
+                specific.push(GroupEvent::Milestone(event));
+            }
+        }
+        Ok(GroupEvent::merge(events, specific))
+    }
+}
+
+// =================================================================================================
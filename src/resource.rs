@@ -0,0 +1,249 @@
+// Copyright (C) 2020 University of Glasgow
+//
+// Redistribution and use in source and binary forms, with or without
+// modification, are permitted provided that the following conditions
+// are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice,
+//    this list of conditions and the following disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright
+//    notice, this list of conditions and the following disclaimer in the
+//    documentation and/or other materials provided with the distribution.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+// AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+// IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE
+// ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE
+// LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR
+// CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF
+// SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS
+// INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN
+// CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE)
+// ARISING IN ANY WAY OUT OF THE USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE
+// POSSIBILITY OF SUCH DAMAGE.
+//
+// SPDX-License-Identifier: BSD-2-Clause
+
+// Traits linking each type returned by the Datatracker to its URI type and
+// the API endpoint that returns it. These allow any URI to be resolved with
+// a single call to Datatracker::get(), for example:
+//   let ad = dt.get(&document.ad.unwrap())?;    // Returns a Person
+//
+// URIs can be parsed, and are checked to be for the right endpoint, or can
+// be built from the id or slug of the resource:
+//   let uri : PersonUri = "/api/v1/person/person/20209/".parse()?;
+//   assert_eq!(uri, PersonUri::from_id(20209));
+//   assert_eq!(uri.id(), Some(20209));
+
+use std::fmt;
+use std::str::FromStr;
+
+use percent_encoding::{utf8_percent_encode, AsciiSet, CONTROLS};
+use serde::Deserialize;
+
+use crate::*;
+
+// =================================================================================================
+
+// A type returned by a Datatracker list endpoint. PATH is the path of that
+// endpoint, e.g., "/api/v1/person/person/"
+pub trait Endpoint {
+    const PATH : &'static str;
+}
+
+
+// A type returned by the Datatracker that is identified by a URI
+pub trait Resource : Endpoint + for<'de> Deserialize<'de> {
+    type Uri : ResourceUri<Resource = Self>;
+}
+
+
+// A URI that identifies a resource in the Datatracker, and that can be
+// resolved to that resource.
+pub trait ResourceUri {
+    type Resource : Resource<Uri = Self>;
+
+    // The URI path, e.g., "/api/v1/person/person/20209/"
+    fn as_str(&self) -> &str;
+}
+
+
+// The identifier in a URI, i.e., the final path segment, if the URI is for
+// a resource returned by the endpoint at path. For example, the identifier
+// in "/api/v1/person/person/20209/" is "20209".
+fn uri_identifier<'a>(uri : &'a str, path : &str) -> Option<&'a str> {
+    let ident = uri.strip_prefix(path)?.strip_suffix('/')?;
+    if ident.is_empty() || ident.contains('/') {
+        None
+    } else {
+        Some(ident)
+    }
+}
+
+
+// Characters that cannot appear in the final path segment of a URI, and are
+// percent-encoded when a URI is built from an identifier such as a slug.
+const IDENTIFIER_ENCODE_SET : &AsciiSet = &CONTROLS.add(b' ').add(b'"').add(b'#').add(b'%').add(b'/')
+                                                   .add(b'<').add(b'>').add(b'?').add(b'`').add(b'{').add(b'}');
+
+fn uri_for_identifier(path : &str, ident : &str) -> String {
+    format!("{}{}/", path, utf8_percent_encode(ident, IDENTIFIER_ENCODE_SET))
+}
+
+
+// Implement parsing, formatting, and the constructor and accessor for a URI
+// type. URIs are identified either by a numeric id, or by a string given by
+// the named accessor, e.g., the slug of a DocStateTypeUri. Identifiers are
+// returned as they appear in the URI, so are percent-encoded.
+macro_rules! uri {
+    ($uri:ident, $path:expr, id) => {
+        uri!($uri, $path);
+
+        impl $uri {
+            pub fn from_id(id : u64) -> Self {
+                $uri(format!("{}{}/", $path, id))
+            }
+
+            pub fn id(&self) -> Option<u64> {
+                uri_identifier(&self.0, $path)?.parse().ok()
+            }
+        }
+
+        impl FromStr for $uri {
+            type Err = DatatrackerError;
+
+            fn from_str(s : &str) -> DTResult<Self> {
+                let uri = $uri(s.to_string());
+                match uri.id() {
+                    Some(_) => Ok(uri),
+                    None    => Err(DatatrackerError::InvalidUri(s.to_string()))
+                }
+            }
+        }
+    };
+    ($uri:ident, $path:expr, $from:ident, $ident:ident) => {
+        uri!($uri, $path);
+
+        impl $uri {
+            pub fn $from($ident : &str) -> Self {
+                $uri(uri_for_identifier($path, $ident))
+            }
+
+            pub fn $ident(&self) -> Option<&str> {
+                uri_identifier(&self.0, $path)
+            }
+        }
+
+        impl FromStr for $uri {
+            type Err = DatatrackerError;
+
+            fn from_str(s : &str) -> DTResult<Self> {
+                match uri_identifier(s, $path) {
+                    Some(_) => Ok($uri(s.to_string())),
+                    None    => Err(DatatrackerError::InvalidUri(s.to_string()))
+                }
+            }
+        }
+    };
+    ($uri:ident, $path:expr) => {
+        impl fmt::Display for $uri {
+            fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "{}", self.0)
+            }
+        }
+
+        // Queries filter on related objects by their id or slug
+        impl QueryValue for $uri {
+            fn to_query_value(&self) -> String {
+                uri_identifier(&self.0, $path).unwrap_or(&self.0).to_string()
+            }
+        }
+    };
+}
+
+
+// Implement the traits for a resource and its URI type, as well as those
+// implemented by uri!() for the URI type.
+macro_rules! resource {
+    ($resource:ident, $uri:ident, $path:expr, $($ident:ident),+) => {
+        uri!($uri, $path, $($ident),+);
+
+        impl Endpoint for $resource {
+            const PATH : &'static str = $path;
+        }
+
+        impl Resource for $resource {
+            type Uri = $uri;
+        }
+
+        impl ResourceUri for $uri {
+            type Resource = $resource;
+
+            fn as_str(&self) -> &str {
+                &self.0
+            }
+        }
+    };
+}
+
+resource!(Email,                  EmailUri,                  "/api/v1/person/email/",               from_address, address);
+resource!(HistoricalEmail,        HistoricalEmailUri,        "/api/v1/person/historicalemail/",     id);
+resource!(Person,                 PersonUri,                 "/api/v1/person/person/",              id);
+resource!(HistoricalPerson,       HistoricalPersonUri,       "/api/v1/person/historicalperson/",    id);
+resource!(PersonAlias,            PersonAliasUri,            "/api/v1/person/alias/",               id);
+resource!(Document,               DocumentUri,               "/api/v1/doc/document/",               from_name, name);
+resource!(DocAlias,               DocAliasUri,               "/api/v1/doc/docalias/",               id);
+resource!(DocumentAuthor,         DocumentAuthorUri,         "/api/v1/doc/documentauthor/",         id);
+resource!(RelatedDocument,        RelatedDocumentUri,        "/api/v1/doc/relateddocument/",        id);
+resource!(Submission,             SubmissionUri,             "/api/v1/submit/submission/",          id);
+resource!(SubmissionCheck,        SubmissionCheckUri,        "/api/v1/submit/submissioncheck/",     id);
+resource!(SubmissionEvent,        SubmissionEventUri,        "/api/v1/submit/submissionevent/",     id);
+resource!(DocState,               DocStateUri,               "/api/v1/doc/state/",                  id);
+resource!(DocStateType,           DocStateTypeUri,           "/api/v1/doc/statetype/",              from_slug, slug);
+resource!(BaseDocEvent,           DocEventUri,               "/api/v1/doc/docevent/",               id);
+resource!(StateDocEvent,          StateDocEventUri,          "/api/v1/doc/statedocevent/",          id);
+resource!(BallotDocEvent,         BallotDocEventUri,         "/api/v1/doc/ballotdocevent/",         id);
+resource!(NewRevisionDocEvent,    NewRevisionDocEventUri,    "/api/v1/doc/newrevisiondocevent/",    id);
+resource!(SubmissionDocEvent,     SubmissionDocEventUri,     "/api/v1/doc/submissiondocevent/",     id);
+resource!(WriteupDocEvent,        WriteupDocEventUri,        "/api/v1/doc/writeupdocevent/",        id);
+resource!(ConsensusDocEvent,      ConsensusDocEventUri,      "/api/v1/doc/consensusdocevent/",      id);
+resource!(BallotPositionDocEvent, BallotPositionDocEventUri, "/api/v1/doc/ballotpositiondocevent/", id);
+resource!(ReviewRequestDocEvent,  ReviewRequestDocEventUri,  "/api/v1/doc/reviewrequestdocevent/",  id);
+resource!(LastCallDocEvent,       LastCallDocEventUri,       "/api/v1/doc/lastcalldocevent/",       id);
+resource!(TelechatDocEvent,       TelechatDocEventUri,       "/api/v1/doc/telechatdocevent/",       id);
+resource!(EditedAuthorsDocEvent,  EditedAuthorsDocEventUri,  "/api/v1/doc/editedauthorsdocevent/",  id);
+resource!(AddedMessageEvent,      AddedMessageEventUri,      "/api/v1/doc/addedmessageevent/",      id);
+resource!(BallotType,             BallotTypeUri,             "/api/v1/doc/ballottype/",             id);
+resource!(DeletedEvent,           DeletedEventUri,           "/api/v1/doc/deletedevent/",           id);
+resource!(Group,                  GroupUri,                  "/api/v1/group/group/",                id);
+resource!(GroupHistory,           GroupHistoryUri,           "/api/v1/group/grouphistory/",         id);
+resource!(Role,                   RoleUri,                   "/api/v1/group/role/",                 id);
+resource!(RoleHistory,            RoleHistoryUri,            "/api/v1/group/rolehistory/",          id);
+resource!(GroupMilestone,         GroupMilestoneUri,         "/api/v1/group/groupmilestone/",       id);
+resource!(GroupMilestoneHistory,  GroupMilestoneHistoryUri,  "/api/v1/group/groupmilestonehistory/", id);
+resource!(MilestoneGroupEvent,    MilestoneGroupEventUri,    "/api/v1/group/milestonegroupevent/",  id);
+resource!(BaseGroupEvent,         GroupEventUri,             "/api/v1/group/groupevent/",           id);
+resource!(ChangeStateGroupEvent,  ChangeStateGroupEventUri,  "/api/v1/group/changestategroupevent/", id);
+resource!(GroupType,              GroupTypeUri,              "/api/v1/name/grouptypename/",         from_slug, slug);
+resource!(GroupState,             GroupStateUri,             "/api/v1/name/groupstatename/",        from_slug, slug);
+
+// URIs of resources that are referenced by other types, but not yet otherwise supported
+uri!(ReviewRequestUri,      "/api/v1/review/reviewrequest/",        id);
+uri!(ReviewRequestStateUri, "/api/v1/name/reviewrequeststatename/", from_slug, slug);
+uri!(MessageUri,            "/api/v1/message/message/",             id);
+uri!(ContentTypeUri,        "/api/v1/contenttypes/contenttype/",    id);
+
+impl Endpoint for DocRelationshipName {
+    const PATH : &'static str = "/api/v1/name/docrelationshipname/";
+}
+
+impl Endpoint for BallotPositionName {
+    const PATH : &'static str = "/api/v1/name/ballotpositionname/";
+}
+
+impl Endpoint for GroupMilestoneStateName {
+    const PATH : &'static str = "/api/v1/name/groupmilestonestatename/";
+}
+
+// =================================================================================================
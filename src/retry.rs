@@ -0,0 +1,201 @@
+// Copyright (C) 2020 University of Glasgow
+//
+// Redistribution and use in source and binary forms, with or without
+// modification, are permitted provided that the following conditions
+// are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice,
+//    this list of conditions and the following disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright
+//    notice, this list of conditions and the following disclaimer in the
+//    documentation and/or other materials provided with the distribution.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+// AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+// IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE
+// ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE
+// LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR
+// CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF
+// SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS
+// INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN
+// CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE)
+// ARISING IN ANY WAY OUT OF THE USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE
+// POSSIBILITY OF SUCH DAMAGE.
+//
+// SPDX-License-Identifier: BSD-2-Clause
+
+// Retry of failed requests, with exponential backoff and jitter, and a
+// client-side limit on the request rate. Requests that fail with a connection
+// error or timeout, or with a "429 Too Many Requests" or 5xx server error, are
+// retried. The delay before each retry doubles, up to a maximum, and is then
+// randomised to avoid many clients retrying in lockstep. A Retry-After header
+// sent by the server is honoured, unless it asks for a longer wait than the
+// maximum backoff, in which case the request fails as rate limited.
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use chrono::prelude::*;
+use rand::Rng;
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use reqwest::StatusCode;
+
+use crate::api::*;
+
+// =================================================================================================
+
+#[derive(Clone, Debug)]
+pub struct RetryPolicy {
+    max_retries     : u32,
+    initial_backoff : Duration,
+    max_backoff     : Duration,
+    jitter          : bool
+}
+
+
+impl RetryPolicy {
+    // The default policy retries up to three times, waiting 0.5s, 1s, and 2s
+    // (less jitter) between attempts.
+    pub fn new() -> Self {
+        RetryPolicy {
+            max_retries     : 3,
+            initial_backoff : Duration::from_millis(500),
+            max_backoff     : Duration::from_secs(30),
+            jitter          : true
+        }
+    }
+
+    // A policy that never retries
+    pub fn none() -> Self {
+        RetryPolicy::new().max_retries(0)
+    }
+
+    pub fn max_retries(mut self, max_retries : u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    pub fn initial_backoff(mut self, backoff : Duration) -> Self {
+        self.initial_backoff = backoff;
+        self
+    }
+
+    pub fn max_backoff(mut self, backoff : Duration) -> Self {
+        self.max_backoff = backoff;
+        self
+    }
+
+    pub fn jitter(mut self, jitter : bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    pub(crate) fn should_retry(&self, attempt : u32) -> bool {
+        attempt < self.max_retries
+    }
+
+    // The delay before retry number attempt (counting from zero), ignoring
+    // any Retry-After header. With jitter enabled, this is chosen uniformly
+    // at random between half and all of the exponential backoff.
+    pub fn backoff(&self, attempt : u32) -> Duration {
+        let backoff = self.initial_backoff
+                          .checked_mul(2u32.saturating_pow(attempt))
+                          .unwrap_or(self.max_backoff)
+                          .min(self.max_backoff);
+        if self.jitter {
+            backoff.mul_f64(rand::thread_rng().gen_range(0.5..=1.0))
+        } else {
+            backoff
+        }
+    }
+
+    // The delay before retry number attempt, given the Retry-After value
+    // sent by the server, if any. The server's value takes precedence, but
+    // is capped at the maximum backoff: if the server asks for a longer wait,
+    // this returns None and the request should not be retried.
+    pub fn delay(&self, attempt : u32, retry_after : Option<Duration>) -> Option<Duration> {
+        match retry_after {
+            Some(retry_after) if retry_after > self.max_backoff => None,
+            Some(retry_after) => Some(retry_after),
+            None              => Some(self.backoff(attempt))
+        }
+    }
+
+    pub fn is_retryable_status(status : StatusCode) -> bool {
+        status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+    }
+
+    pub fn is_retryable_error(err : &reqwest::Error) -> bool {
+        err.is_timeout() || err.is_connect()
+    }
+}
+
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+
+// Parse a Retry-After header, that can be either a number of seconds
+// or an HTTP date (e.g., "Wed, 21 Oct 2015 07:28:00 GMT").
+pub fn retry_after(headers : &HeaderMap) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+    let date = DateTime::parse_from_rfc2822(value).ok()?.with_timezone(&Utc);
+    Some((date - Utc::now()).to_std().unwrap_or_else(|_| Duration::from_secs(0)))
+}
+
+// =================================================================================================
+
+// The longest interval between requests that a rate limit can impose. Much
+// longer intervals overflow when added to the current time.
+const MAX_RATE_LIMIT_INTERVAL : Duration = Duration::from_secs(24 * 60 * 60);
+
+// Limits the rate at which requests are sent, by spacing them out evenly.
+#[derive(Debug)]
+pub struct RateLimiter {
+    interval : Duration,
+    next     : Mutex<Option<Instant>>
+}
+
+
+impl RateLimiter {
+    // The rate must be at least one request per day.
+    pub fn new(requests_per_second : f64) -> DTResult<Self> {
+        if requests_per_second.is_nan() || requests_per_second <= 0.0 {
+            return Err(DatatrackerError::InvalidRateLimit(requests_per_second));
+        }
+        let interval = match Duration::try_from_secs_f64(1.0 / requests_per_second) {
+            Ok(interval) if interval <= MAX_RATE_LIMIT_INTERVAL => interval,
+            _ => return Err(DatatrackerError::InvalidRateLimit(requests_per_second))
+        };
+        Ok(RateLimiter {
+            interval,
+            next     : Mutex::new(None)
+        })
+    }
+
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    // Reserve a slot for a request, returning how long the caller must wait
+    // before sending it.
+    pub fn reserve(&self) -> Duration {
+        let now  = Instant::now();
+        let mut next = self.next.lock().unwrap();
+        let slot = match *next {
+            Some(t) if t > now => t,
+            _                  => now
+        };
+        *next = Some(slot + self.interval);
+        slot - now
+    }
+}
+
+// =================================================================================================
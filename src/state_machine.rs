@@ -0,0 +1,201 @@
+// Copyright (C) 2019 University of Glasgow
+// 
+// Redistribution and use in source and binary forms, with or without
+// modification, are permitted provided that the following conditions 
+// are met:
+// 
+// 1. Redistributions of source code must retain the above copyright notice,
+//    this list of conditions and the following disclaimer.
+// 
+// 2. Redistributions in binary form must reproduce the above copyright
+//    notice, this list of conditions and the following disclaimer in the
+//    documentation and/or other materials provided with the distribution.
+// 
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+// AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+// IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE
+// ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE
+// LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR
+// CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF
+// SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS
+// INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN
+// CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE)
+// ARISING IN ANY WAY OUT OF THE USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE
+// POSSIBILITY OF SUCH DAMAGE.
+//
+// SPDX-License-Identifier: BSD-2-Clause
+
+
+// A model of the states that documents can be in, built from the lists of
+// document states and state types. Each state type, e.g., "draft-iesg" for
+// the IESG state of a draft, is a state machine: the next_states of each
+// DocState list the states that it can move to. For example:
+//   let sm   = dt.state_machine()?;
+//   let iesg = sm.current_state(&doc, &DocStateTypeUri::from_slug("draft-iesg"));
+
+use std::collections::{BTreeMap, HashSet, VecDeque};
+
+use crate::*;
+
+// =================================================================================================
+
+pub struct StateMachine {
+    types  : BTreeMap<DocStateTypeUri, DocStateType>,
+    states : BTreeMap<DocStateUri, DocState>
+}
+
+
+impl StateMachine {
+    pub fn new(types : Vec<DocStateType>, states : Vec<DocState>) -> Self {
+        StateMachine {
+            types  : types.into_iter().map(|t| (t.resource_uri.clone(), t)).collect(),
+            states : states.into_iter().map(|s| (s.resource_uri.clone(), s)).collect()
+        }
+    }
+
+    pub fn state_type(&self, type_uri : &DocStateTypeUri) -> Option<&DocStateType> {
+        self.types.get(type_uri)
+    }
+
+    pub fn state_types(&self) -> impl Iterator<Item = &DocStateType> {
+        self.types.values()
+    }
+
+    pub fn state(&self, state_uri : &DocStateUri) -> Option<&DocState> {
+        self.states.get(state_uri)
+    }
+
+    // The states of a state type, ordered by DocState::order
+    pub fn states(&self, type_uri : &DocStateTypeUri) -> Vec<&DocState> {
+        let mut states = self.states.values().filter(|s| &s.state_type == type_uri).collect::<Vec<_>>();
+        states.sort_by_key(|s| (s.order, s.id));
+        states
+    }
+
+    // Whether a document can move directly from one state to another. Both
+    // states must be of the same type.
+    pub fn is_valid_transition(&self, from : &DocStateUri, to : &DocStateUri) -> bool {
+        match (self.state(from), self.state(to)) {
+            (Some(from), Some(to)) => from.state_type == to.state_type && from.next_states.contains(&to.resource_uri),
+            _                      => false
+        }
+    }
+
+    // The states of the same type that can be reached from a state in one
+    // or more valid transitions, nearest first
+    pub fn reachable_states(&self, from : &DocStateUri) -> Vec<&DocState> {
+        let mut seen      = HashSet::new();
+        let mut queue     = VecDeque::new();
+        let mut reachable = Vec::new();
+        let from = match self.state(from) {
+            Some(state) => state,
+            None        => return reachable
+        };
+        queue.extend(&from.next_states);
+        while let Some(uri) = queue.pop_front() {
+            if seen.insert(uri) {
+                // As for is_valid_transition(), next states of another type are ignored
+                if let Some(state) = self.state(uri).filter(|s| s.state_type == from.state_type) {
+                    queue.extend(&state.next_states);
+                    reachable.push(state);
+                }
+            }
+        }
+        reachable
+    }
+
+    // The states of a state type that have no next states. For state types
+    // where the Datatracker doesn't record the transitions, this is all of
+    // the states.
+    pub fn terminal_states(&self, type_uri : &DocStateTypeUri) -> Vec<&DocState> {
+        self.states(type_uri).into_iter().filter(|s| s.next_states.is_empty()).collect()
+    }
+
+    // The current state of a document for each type of state it has
+    pub fn current_states(&self, doc : &Document) -> BTreeMap<DocStateTypeUri, &DocState> {
+        doc.states.iter()
+                  .filter_map(|uri| self.state(uri))
+                  .map(|state| (state.state_type.clone(), state))
+                  .collect()
+    }
+
+    // The current state of a document of the given type, e.g., the state
+    // of type "draft-iesg" is the IESG state of a draft
+    pub fn current_state(&self, doc : &Document, type_uri : &DocStateTypeUri) -> Option<&DocState> {
+        doc.states.iter()
+                  .filter_map(|uri| self.state(uri))
+                  .find(|state| &state.state_type == type_uri)
+    }
+
+    // The state machine for a state type as a Graphviz DOT digraph. States
+    // are listed in order, and unused states are drawn dashed and grey.
+    pub fn to_dot(&self, type_uri : &DocStateTypeUri) -> String {
+        let mut dot = format!("digraph \"{}\" {{\n", escape_label(&self.type_slug(type_uri)));
+        if let Some(state_type) = self.state_type(type_uri) {
+            dot.push_str(&format!("    label=\"{}\";\n", escape_label(&state_type.label)));
+        }
+        let states = self.states(type_uri);
+        for state in &states {
+            let style = if state.used { "" } else { ", style=dashed, color=grey, fontcolor=grey" };
+            dot.push_str(&format!("    s{} [label=\"{}\"{}];\n", state.id, escape_label(&state.name), style));
+        }
+        for (from, to) in self.transitions(&states) {
+            dot.push_str(&format!("    s{} -> s{};\n", from.id, to.id));
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    // The state machine for a state type as a Mermaid state diagram. States
+    // are listed in order, and unused states are given the class "unused".
+    pub fn to_mermaid(&self, type_uri : &DocStateTypeUri) -> String {
+        let mut mermaid = String::from("stateDiagram-v2\n");
+        let states = self.states(type_uri);
+        for state in &states {
+            mermaid.push_str(&format!("    s{} : {}\n", state.id, escape_mermaid(&state.name)));
+        }
+        for (from, to) in self.transitions(&states) {
+            mermaid.push_str(&format!("    s{} --> s{}\n", from.id, to.id));
+        }
+        let unused = states.iter().filter(|s| !s.used).map(|s| format!("s{}", s.id)).collect::<Vec<_>>();
+        if !unused.is_empty() {
+            mermaid.push_str("    classDef unused stroke-dasharray: 5 5, color: grey\n");
+            mermaid.push_str(&format!("    class {} unused\n", unused.join(",")));
+        }
+        mermaid
+    }
+
+    fn type_slug(&self, type_uri : &DocStateTypeUri) -> String {
+        match self.state_type(type_uri) {
+            Some(state_type) => state_type.slug.clone(),
+            None             => type_uri.slug().unwrap_or("").to_string()
+        }
+    }
+
+    // The transitions between the given states, ordered by source state
+    fn transitions<'a>(&'a self, states : &[&'a DocState]) -> Vec<(&'a DocState, &'a DocState)> {
+        let mut transitions = Vec::new();
+        for from in states {
+            let mut next = from.next_states.iter()
+                                           .filter_map(|uri| self.state(uri))
+                                           .filter(|to| to.state_type == from.state_type)
+                                           .collect::<Vec<_>>();
+            next.sort_by_key(|s| (s.order, s.id));
+            transitions.extend(next.into_iter().map(|to| (*from, to)));
+        }
+        transitions
+    }
+}
+
+
+fn escape_label(label : &str) -> String {
+    label.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+
+// Mermaid state descriptions end at a newline
+fn escape_mermaid(label : &str) -> String {
+    label.replace('\n', " ")
+}
+
+// =================================================================================================